@@ -1,93 +1,2313 @@
+mod idiomes;
+mod llegeix;
+mod noms_propis;
+mod numerals;
+
+pub(crate) use llegeix::Molecula;
+pub use llegeix::{ParseError, Structure, parse_name};
+
 use crate::*;
+use idiomes::{Lexic, Motiu, Quins};
+use std::cmp::Ordering;
 
-const PREFIX_SEGONS_MIDA: [&str; 11] = [
-    "?", "met", "et", "prop", "but", "pent", "hexa", "hept", "oct", "non", "dec",
+/// Taula de prioritats del grup principal (la de `lib.rs`), de més a menys. Els sufixos i
+/// prefixos de cada idioma són a `idiomes`
+const GRUPS_PRINCIPALS: [UiRadical; 8] = [
+    UiRadical::Carboxil,
+    UiRadical::Ester,
+    UiRadical::Amida,
+    UiRadical::Nitril,
+    UiRadical::Aldehid,
+    UiRadical::Cetona,
+    UiRadical::Alcohol,
+    UiRadical::Amina,
 ];
 
-// TODO: Press N or something to get the name of the molecule you're hovering over (i.e. only name
-//       its connected graph). Print "nothing under cursor" instead if there's nothing there
-//
-// Return value may also be an error message to be displayed directly (too lazy to make a proper
-// error enum and impl Display on it)
-pub fn anomena(input: &[UiBlock], source: &UiBlock) -> String {
-    let index = input
-        .iter()
-        .position(|b| b.id == source.id)
-        .expect("block existed and then didn't in the same frame");
-    let Ok(molecula) = find_connex(input, index) else {
-        return "ERR: La molecula conté un cicle :c".to_string();
+/// L'idioma del nom
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    Catalan,
+    Spanish,
+    English,
+}
+
+/// L'edició de les recomanacions de la IUPAC que se segueix
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Edition {
+    /// El primer localitzador va davant de l'arrel: "2-butanol", "1,3-butadiè"
+    Iupac1979,
+    /// "butan-2-ol", "buta-1,3-diè"
+    #[default]
+    Iupac1993,
+    /// Com la del 1993, però un anell sempre guanya una cadena, i la cadena més llarga guanya
+    /// la més insaturada: "3-metilidenheptà"
+    Iupac2013,
+}
+
+/// Si es dona el nom propi dels compostos que en tenen
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrivialNames {
+    /// Només el sistemàtic: "propanona"
+    #[default]
+    Hidden,
+    /// El sistemàtic, amb el propi entre parèntesis: "propanona (acetona)"
+    Alongside,
+    /// El propi en lloc del sistemàtic: "acetona"
+    Instead,
+}
+
+/// Com es vol el nom. Per defecte, el de la nomenclatura del 1993 en català
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NamingOptions {
+    pub language: Language,
+    pub edition: Edition,
+    /// Anomena els èters com a "etil metil èter" en lloc de "metoxietà"
+    pub functional_class_ethers: bool,
+    /// Anomena els benzens amb dues posicions ocupades com a "o-diclorobenzè" en lloc de
+    /// "1,2-diclorobenzè"
+    pub ortho_meta_para: bool,
+    /// Si es dona el nom propi, quan el compost en té. Si no en sabem el sistemàtic, es dona
+    /// igualment
+    pub trivial_names: TrivialNames,
+    /// Anomena els substituents ramificats amb els noms curts acceptats, "isopropil" o
+    /// "terc-butil", en lloc de "(1-metiletil)" o "(1,1-dimetiletil)"
+    pub short_substituent_names: bool,
+}
+
+/// Per què no s'ha pogut anomenar una molècula. `Display` en dona el missatge per a l'usuari
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NamingError {
+    /// Un anell que encara no sabem anomenar (fusionat, amb heteroàtoms...)
+    Cycle(String),
+    /// La molècula no té cap carboni
+    NoCarbon,
+    /// N'hi ha més d'una, sense enllaçar
+    Disconnected,
+    /// Un enllaç que els radicals no poden fer, com un doble enllaç amb un O
+    InvalidValence(String),
+    /// Una part de la molècula que encara no sabem anomenar
+    UnsupportedGroup(String),
+}
+
+impl std::fmt::Display for NamingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cycle(que) | Self::UnsupportedGroup(que) => {
+                write!(f, "Encara no sé anomenar {que}")
+            }
+            Self::NoCarbon => write!(f, "La molecula (sota el cursor) ha de contindre carboni"),
+            Self::Disconnected => write!(f, "Hi ha més d'una molecula"),
+            Self::InvalidValence(que) => write!(f, "No sé anomenar {que}"),
+        }
+    }
+}
+
+/// Una molècula anomenada. `Display` en dona el nom sencer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Naming {
+    /// El nom, tal com el demanen les opcions
+    pub name: String,
+    /// El nom propi del compost, si en té
+    pub trivial_name: Option<&'static str>,
+    /// El nom sistemàtic per parts. Només falta si el compost té nom propi però encara no en
+    /// sabem formar el sistemàtic
+    pub systematic: Option<SystematicName>,
+}
+
+impl std::fmt::Display for Naming {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Les parts d'un nom sistemàtic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystematicName {
+    /// El nom sencer
+    pub name: String,
+    pub parent: ParentChain,
+    /// Localitzadors dels grups principals a la cadena principal
+    pub principal_locants: Vec<usize>,
+    /// (localitzador, nom) de cada substituent, com (2, "metil")
+    pub substituents: Vec<(usize, String)>,
+    /// La terminació del grup principal ("ol", "oic"...), si n'hi ha
+    pub suffix: Option<&'static str>,
+    /// Per què el nom és aquest, pas a pas
+    pub explanation: Vec<Step>,
+}
+
+/// Un pas de l'explicació d'un nom: quina norma, numerada com a la llista d'`anomena_molecula`, ha
+/// decidit què. `Display` en dona "2d: s'escull la cadena de 6 carbonis davant la de 5 carbonis"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub rule: &'static str,
+    pub text: String,
+}
+
+impl std::fmt::Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.rule, self.text)
+    }
+}
+
+/// La cadena (o l'anell) principal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParentChain {
+    pub kind: ParentKind,
+    pub carbons: usize,
+    /// Localitzadors dels dobles enllaços. Els d'un benzè no hi són
+    pub double_bonds: Vec<usize>,
+    /// Localitzadors dels triples enllaços
+    pub triple_bonds: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParentKind {
+    Chain,
+    Ring,
+    Benzene,
+}
+
+/// Anomena una molècula, que ha de ser una de sola (un component connex). Les normes, que
+/// són les que numeren els passos de l'explicació:
+///
+/// 1. S'ha de triar la funció principal a partir de l'ordre de prioritat.
+/// 2. S'ha de triar la cadena principal aplicant les normes, en l'ordre en què figuren a la
+///    llista, fins trobar-ne una que decideixi, en cas de dues o més cadenes iguals:
+///    - a) aquella que conté el grup principal
+///    - b) aquella que té més grups principals
+///    - c) aquella més insaturada (amb més dobles i triples enllaços en conjunt)
+///    - d) aquella més llarga
+///    - e) aquella que té més dobles enllaços
+///    - f) la que presenti els localitzadors més baixos i en l'ordre de prioritat que s'indica
+///      per: 1) grups principals, 2) enllaços múltiples en conjunt, 3) dobles enllaços
+///    - g) la que tengui el major número possible de substituents i, en cas d'igualtat, la que
+///      els assigni els números més baixos
+/// 3. S'ha de numerar la cadena principal, des d'un extrem i assignant els números més baixos,
+///    en ordre d'importància, a:
+///    - a) al(s) grup(s) principal(s)
+///    - b) a dobles i triples enllaços en conjunt. En cas d'igualtat, als dobles enllaços.
+///    - c) als substituents; en igualtat de condicions tenen preferència segons l'ordre
+///      alfabètic.
+/// 4. Es forma el nom, començant pels substituents en ordre alfabètic o de complexitat; a
+///    continuació la cadena principal acabada amb la terminació del grup principal
+pub fn anomena_molecula(molecula: &Molecule, opts: &NamingOptions) -> Result<Naming, NamingError> {
+    if !molecula.groups.iter().any(UiRadical::contains_carbon) {
+        return Err(NamingError::NoCarbon);
+    }
+    if molecula.components().len() > 1 {
+        return Err(NamingError::Disconnected);
+    }
+    if let Some(problema) = molecula.valence_problem() {
+        return Err(NamingError::InvalidValence(problema));
+    }
+    // Els àtoms sols es pleguen en grups, i els que no en fan cap no se saben anomenar
+    let agrupada;
+    let molecula = match molecula.groups.iter().any(UiRadical::is_bare_atom) {
+        true => {
+            agrupada = agrupa_molecula(molecula);
+            &agrupada
+        }
+        false => molecula,
     };
-    let links = UiBlock::count_links(&molecula);
-
-    if !molecula.iter().any(|b| b.radical.contains_carbon()) {
-        return "ERR: La molecula (sota el cursor) ha de contindre carboni".to_string();
-    }
-    return "Encara noooo".to_string();
-    return todo!();
-
-    // 1. S'ha de triar la funció principal a partir de l'ordre de prioritat.
-    // 2. S'ha de triar la cadena principal aplicant les normes, en l'ordre en què figuren a la llista, fins trobar-ne una que decideixi, en cas de dues o més cadenes iguals:
-    //    a) aquella que conté el grup principal
-    //    b) aquella que té més grups principals
-    //    c) aquella més insaturada (amb més dobles i triples enllaços en conjunt)
-    //    d) aquella més llarga
-    //    e) aquella que té més dobles enllaços
-    //    f) la que presenti els localitzadors més baixos i en l'ordre de prioritat que s'indica per:
-    //       1) grups principals
-    //       2) enllaços múltiples en conjunt
-    //       3) dobles enllaços
-    //    g) la que tengui el major número possible de substituents i, en cas d'igualtat, la que els assigni els números més baixos
-    // 3. S'ha de numerar la cadena principal, des d'un extrem i assignant els números més baixos, en
-    // ordre d'importància, a:
-    //    a) al(s) grup(s) principal(s)
-    //    b) a dobles i triples enllaços en conjunt. En cas d'igualtat, als dobles enllaços.
-    //    c) als substituents; en igualtat de condicions tenen preferència segons l'ordre alfabètic.
-    // 4. Es forma el nom, començant pels substituents en ordre alfabètic o de complexitat; a
-    // continuació la cadena principal acabada amb la terminació del grup principal
-
-    // TODO: ASSUMEIXO HIDROCARBUR PER ARA
+    if let Some(r) = molecula.groups.iter().find(|r| r.is_bare_atom()) {
+        return Err(NamingError::UnsupportedGroup(format!(
+            "un {r} que no forma cap grup"
+        )));
+    }
 
-    // 1. Trio funció principal
-    let principal = {
-        let v = molecula.iter().map(|b| b.radical).collect::<Vec<_>>();
+    let graf = Graf::new(molecula);
+    let trivial_name = noms_propis::nom_propi(molecula, opts.language);
+    let (name, systematic) = match (anomena_graf(&graf, opts), trivial_name) {
+        (Err(e), None) => return Err(e),
+        (Err(_), Some(propi)) => (propi.to_string(), None),
+        (Ok(s), Some(propi)) => match opts.trivial_names {
+            TrivialNames::Hidden => (s.name.clone(), Some(s)),
+            TrivialNames::Alongside => (format!("{} ({propi})", s.name), Some(s)),
+            TrivialNames::Instead => (propi.to_string(), Some(s)),
+        },
+        (Ok(s), None) => (s.name.clone(), Some(s)),
+    };
+    Ok(Naming {
+        name,
+        trivial_name,
+        systematic,
+    })
+}
+
+/// Els noms curts acceptats dels substituents ramificats, com a (prefix, carbonis en total,
+/// carbonis de la cadena, localitzadors dels metils). "terc-" va en l'idioma de cada `Lexic`
+const NOMS_CURTS: [(&str, usize, usize, &[usize]); 7] = [
+    ("iso", 3, 2, &[1]),
+    ("iso", 4, 3, &[2]),
+    ("sec-", 4, 3, &[1]),
+    ("terc-", 4, 2, &[1, 1]),
+    ("iso", 5, 4, &[3]),
+    ("neo", 5, 3, &[2, 2]),
+    ("terc-", 5, 3, &[1, 1]),
+];
+
+/// La molècula com a graf, indexat per posició. `adj[i]` són els veïns de `nodes[i]` juntament
+/// amb la multiplicitat de l'enllaç
+struct Graf {
+    nodes: Vec<UiRadical>,
+    adj: Vec<Vec<(usize, usize)>>,
+    /// `ports[i]` són els (port, veí) de `nodes[i]`, per als radicals on el port importa
+    ports: Vec<Vec<(usize, usize)>>,
+    /// Els anells (SSSR), cada un amb els nodes en l'ordre en què es recorren
+    anells: Vec<Vec<usize>>,
+}
+
+/// Un enllaç entre dos blocs, com a ((bloc, port), (bloc, port))
+pub(crate) type EnllacDeBlocs = ((usize, usize), (usize, usize));
+
+impl Graf {
+    fn new(molecula: &Molecule) -> Self {
+        Self::de_blocs(&molecula.groups, &molecula.links())
+    }
+    /// Com `new`, a partir de només els radicals dels blocs i els enllaços entre ells
+    fn de_blocs(radicals: &[UiRadical], enllacos: &[EnllacDeBlocs]) -> Self {
+        // Cada bloc és un node, menys els benzens, que són sis carbonis (un per port) amb els
+        // dobles enllaços alternats
+        let mut primer = vec![];
+        let mut nodes = vec![];
+        for r in radicals {
+            primer.push(nodes.len());
+            match r {
+                UiRadical::Benze => nodes.extend([UiRadical::C; 6]),
+                r => nodes.push(*r),
+            }
+        }
+        let node = |i: usize, port: usize| match radicals[i] {
+            UiRadical::Benze => primer[i] + port,
+            _ => primer[i],
+        };
+
+        let mut adj: Vec<Vec<(usize, usize)>> = vec![vec![]; nodes.len()];
+        let mut ports: Vec<Vec<(usize, usize)>> = vec![vec![]; nodes.len()];
+        let mut enllaca = |a: usize, b: usize| {
+            for (x, y) in [(a, b), (b, a)] {
+                match adj[x].iter_mut().find(|(v, _)| *v == y) {
+                    Some((_, m)) => *m += 1,
+                    None => adj[x].push((y, 1)),
+                }
+            }
+        };
+        for (i, r) in radicals.iter().enumerate() {
+            if *r == UiRadical::Benze {
+                for k in 0..6 {
+                    let (a, b) = (primer[i] + k, primer[i] + (k + 1) % 6);
+                    enllaca(a, b);
+                    if k % 2 == 0 {
+                        enllaca(a, b);
+                    }
+                }
+            }
+        }
+        for &((i, p), (j, q)) in enllacos {
+            let (x, y) = (node(i, p), node(j, q));
+            enllaca(x, y);
+            ports[x].push((p, y));
+            ports[y].push((q, x));
+        }
+        let anells = anells_minims(&adj);
+        let mut g = Self {
+            nodes,
+            adj,
+            ports,
+            anells,
+        };
+        for i in 0..g.nodes.len() {
+            let enllacos: usize = g.adj[i].iter().map(|(_, m)| m).sum();
+            match g.nodes[i] {
+                // Un CO amb un enllaç buit (un H) és un aldehid
+                UiRadical::Cetona if enllacos < 2 => g.nodes[i] = UiRadical::Aldehid,
+                // Un O amb un enllaç buit és un alcohol
+                UiRadical::Eter if enllacos < 2 => g.nodes[i] = UiRadical::Alcohol,
+                // Un COO amb l'oxigen buit (un H) és un àcid
+                UiRadical::Ester if g.costat_alcoxi(i).is_none() => {
+                    g.nodes[i] = UiRadical::Carboxil
+                }
+                _ => {}
+            }
+        }
+        g
+    }
+    fn vei_al_port(&self, i: usize, port: usize) -> Option<usize> {
+        self.ports[i]
+            .iter()
+            .find(|(p, _)| *p == port)
+            .map(|(_, v)| *v)
+    }
+    /// El veí del costat del carboni d'un èster (vegeu `UiRadical::Ester`)
+    fn costat_acil(&self, ester: usize) -> Option<usize> {
+        self.vei_al_port(ester, 0)
+    }
+    /// El veí del costat de l'oxigen d'un èster (vegeu `UiRadical::Ester`)
+    fn costat_alcoxi(&self, ester: usize) -> Option<usize> {
+        self.vei_al_port(ester, 1)
+    }
+    fn multiplicitat(&self, a: usize, b: usize) -> usize {
+        self.adj[a]
+            .iter()
+            .find(|(v, _)| *v == b)
+            .map_or(0, |(_, m)| *m)
+    }
+    fn anell_de(&self, i: usize) -> Option<&[usize]> {
+        self.anells.iter().find(|a| a.contains(&i)).map(|a| &a[..])
+    }
+}
+
+/// El conjunt mínim d'anells més petits (SSSR) d'un graf connex, segons Horton: per a cada arrel
+/// i cada enllaç es forma l'anell dels dos camins més curts fins a l'arrel, i dels més petits
+/// se'n queden els que són independents
+fn anells_minims(adj: &[Vec<(usize, usize)>]) -> Vec<Vec<usize>> {
+    let n = adj.len();
+    let mut arestes: Vec<(usize, usize)> = vec![];
+    for (a, veins) in adj.iter().enumerate() {
+        arestes.extend(veins.iter().filter(|(b, _)| a < *b).map(|(b, _)| (a, *b)));
+    }
+    let rang = (arestes.len() + 1).saturating_sub(n);
+    if rang == 0 {
+        return vec![];
+    }
+
+    let mut candidats: Vec<Vec<usize>> = vec![];
+    for arrel in 0..n {
+        let mut pares = vec![None; n];
+        pares[arrel] = Some(arrel);
+        let mut cua = std::collections::VecDeque::from([arrel]);
+        while let Some(i) = cua.pop_front() {
+            for &(v, _) in &adj[i] {
+                if pares[v].is_none() {
+                    pares[v] = Some(i);
+                    cua.push_back(v);
+                }
+            }
+        }
+        // De `i` fins a l'arrel
+        let cami = |mut i: usize| {
+            let mut c = vec![i];
+            while i != arrel {
+                i = pares[i].expect("the graph is connected");
+                c.push(i);
+            }
+            c
+        };
+        for &(x, y) in &arestes {
+            let (cx, cy) = (cami(x), cami(y));
+            // Els dos camins només poden compartir l'arrel
+            if cx.iter().filter(|v| cy.contains(v)).count() != 1 {
+                continue;
+            }
+            let mut anell: Vec<usize> = cx.into_iter().rev().collect();
+            anell.extend(cy.into_iter().take_while(|v| *v != arrel));
+            if anell.len() >= 3 {
+                candidats.push(anell);
+            }
+        }
+    }
+    candidats.sort_by_key(Vec::len);
+
+    // Eliminació gaussiana (mòdul 2) sobre els enllaços de cada anell
+    let mut base: Vec<Vec<bool>> = vec![];
+    let mut anells = vec![];
+    for anell in candidats {
+        let mut v = vec![false; arestes.len()];
+        for k in 0..anell.len() {
+            let (a, b) = (anell[k], anell[(k + 1) % anell.len()]);
+            let aresta = arestes
+                .iter()
+                .position(|e| *e == (a.min(b), a.max(b)))
+                .expect("consecutive nodes of a ring are linked");
+            v[aresta] = true;
+        }
+        for b in &base {
+            let pivot = b.iter().position(|x| *x).expect("no empty rows");
+            if v[pivot] {
+                v.iter_mut().zip(b).for_each(|(x, y)| *x ^= *y);
+            }
+        }
+        if v.iter().any(|x| *x) {
+            base.push(v);
+            anells.push(anell);
+            if anells.len() == rang {
+                break;
+            }
+        }
+    }
+    anells
+}
+
+/// Una possible cadena principal, ja numerada: `cadena[0]` té el localitzador 1
+struct Candidat {
+    cadena: Vec<usize>,
+    /// Si la cadena és un anell, i per tant el darrer node s'enllaça amb el primer
+    es_anell: bool,
+    /// Si l'anell és un benzè. Llavors els seus dobles enllaços no compten
+    es_benze: bool,
+    /// Localitzadors dels grups principals, siguin de la cadena o hi pengin
+    principals: Vec<usize>,
+    /// Localitzadors dels dobles enllaços de la cadena
+    dobles: Vec<usize>,
+    /// Localitzadors dels triples enllaços de la cadena
+    triples: Vec<usize>,
+    /// (localitzador, nom). El nom pot ser un error si encara no el sabem anomenar, però
+    /// això només importa si la cadena acaba essent la principal
+    substituents: Vec<(usize, Result<String, NamingError>)>,
+}
+
+impl Candidat {
+    fn new(
+        g: &Graf,
+        lx: &Lexic,
+        cadena: Vec<usize>,
+        es_anell: bool,
+        principal: Option<UiRadical>,
+    ) -> Self {
+        let (mut dobles, mut triples) = (vec![], vec![]);
+        let n = cadena.len();
+        let enllacos = if es_anell { n } else { n - 1 };
+        for i in 0..enllacos {
+            match g.multiplicitat(cadena[i], cadena[(i + 1) % n]) {
+                2 => dobles.push(i + 1),
+                3 => triples.push(i + 1),
+                _ => {}
+            }
+        }
+        let es_benze = es_anell && es_benze(g, &cadena);
+        if es_benze {
+            dobles.clear();
+        }
+        let (mut principals, mut substituents) = (vec![], vec![]);
+        for (i, &node) in cadena.iter().enumerate() {
+            if Some(g.nodes[node]) == principal {
+                principals.push(i + 1);
+            } else if g.nodes[node] == UiRadical::Cetona {
+                let oxo = lx.prefix(UiRadical::Cetona).expect("groups have a prefix");
+                substituents.push((i + 1, Ok(oxo.to_string())));
+            }
+            for &(v, _) in g.adj[node].iter().filter(|(v, _)| !cadena.contains(v)) {
+                let es_principal = Some(g.nodes[v]) == principal
+                    && (!g.nodes[v].contains_carbon()
+                        // Un grup terminal que penja d'un anell: "ciclohexancarboxílic"
+                        || es_anell
+                            && match g.nodes[v] {
+                                UiRadical::Ester => g.costat_acil(v) == Some(node),
+                                r => es_terminal(r),
+                            });
+                if es_principal {
+                    principals.push(i + 1);
+                } else if g.nodes[node] == UiRadical::Ester
+                    && Some(g.nodes[node]) == principal
+                    && g.costat_alcoxi(node) == Some(v)
+                {
+                    // L'alquil de l'èster va a part, "... de metil"
+                } else {
+                    substituents.push((i + 1, nom_substituent(g, lx, v, node)));
+                }
+            }
+        }
+        Self {
+            cadena,
+            es_anell,
+            es_benze,
+            principals,
+            dobles,
+            triples,
+            substituents,
+        }
+    }
+    /// Els nodes de la cadena, sense importar la numeració
+    fn nodes(&self) -> Vec<usize> {
+        let mut v = self.cadena.clone();
         v.sort();
-        v.first()
-            .expect("cannot be empty, we're given at least one block of it")
+        v
+    }
+    fn multiples(&self) -> Vec<usize> {
+        let mut v = [&self.dobles[..], &self.triples[..]].concat();
+        v.sort();
+        v
+    }
+    fn localitzadors_substituents(&self) -> Vec<usize> {
+        let mut v: Vec<usize> = self.substituents.iter().map(|(l, _)| *l).collect();
+        v.sort();
+        v
+    }
+    /// Localitzadors segons l'ordre alfabètic dels substituents (norma 3c)
+    fn localitzadors_alfabetics(&self) -> Vec<usize> {
+        let mut v: Vec<(String, usize)> = self
+            .substituents
+            .iter()
+            .map(|(l, nom)| (nom.as_deref().map(clau_alfabetica).unwrap_or_default(), *l))
+            .collect();
+        v.sort();
+        v.into_iter().map(|(_, l)| l).collect()
+    }
+}
+
+/// Una de les normes per triar i numerar la cadena principal, en l'ordre en què s'apliquen
+struct Norma {
+    /// Com es diu a la llista d'`anomena_molecula`, quan tria entre dues cadenes i quan tria entre dues
+    /// numeracions de la mateixa
+    seleccio: &'static str,
+    numeracio: &'static str,
+    /// `Ordering::Less` vol dir que `a` és millor cadena principal que `b`
+    compara: fn(&Candidat, &Candidat) -> Ordering,
+    /// Per què `a` guanya `b`
+    explica: fn(&Candidat, &Candidat) -> Motiu,
+}
+
+const NORMES: [Norma; 12] = [
+    Norma {
+        seleccio: "2b",
+        numeracio: "2b",
+        compara: |a, b| b.principals.len().cmp(&a.principals.len()),
+        explica: |a, b| Motiu::MesPrincipals(a.principals.len(), b.principals.len()),
+    },
+    // Entre un anell i una cadena, el que tengui més carbonis; si empaten, l'anell
+    Norma {
+        seleccio: "2d",
+        numeracio: "2d",
+        compara: |a, b| match (a.es_anell, b.es_anell) {
+            (true, false) => b.cadena.len().cmp(&a.cadena.len()).then(Ordering::Less),
+            (false, true) => b.cadena.len().cmp(&a.cadena.len()).then(Ordering::Greater),
+            _ => Ordering::Equal,
+        },
+        explica: |a, b| Motiu::AnellOCadena(a.es_anell, a.cadena.len(), b.cadena.len()),
+    },
+    // Al 2013, l'anell sempre
+    Norma {
+        seleccio: "2d",
+        numeracio: "2d",
+        compara: |a, b| b.es_anell.cmp(&a.es_anell),
+        explica: |_, _| Motiu::Anell,
+    },
+    Norma {
+        seleccio: "2c",
+        numeracio: "2c",
+        compara: |a, b| b.multiples().len().cmp(&a.multiples().len()),
+        explica: |a, b| Motiu::MesInsaturada(a.multiples().len(), b.multiples().len()),
+    },
+    Norma {
+        seleccio: "2d",
+        numeracio: "2d",
+        compara: |a, b| b.cadena.len().cmp(&a.cadena.len()),
+        explica: |a, b| Motiu::MesLlarga(a.cadena.len(), b.cadena.len()),
+    },
+    Norma {
+        seleccio: "2e",
+        numeracio: "2e",
+        compara: |a, b| b.dobles.len().cmp(&a.dobles.len()),
+        explica: |a, b| Motiu::MesDobles(a.dobles.len(), b.dobles.len()),
+    },
+    Norma {
+        seleccio: "2f",
+        numeracio: "3a",
+        compara: |a, b| a.principals.cmp(&b.principals),
+        explica: |a, b| {
+            Motiu::Localitzadors(
+                Quins::Principals,
+                a.principals.clone(),
+                b.principals.clone(),
+            )
+        },
+    },
+    Norma {
+        seleccio: "2f",
+        numeracio: "3b",
+        compara: |a, b| a.multiples().cmp(&b.multiples()),
+        explica: |a, b| Motiu::Localitzadors(Quins::Multiples, a.multiples(), b.multiples()),
+    },
+    Norma {
+        seleccio: "2f",
+        numeracio: "3b",
+        compara: |a, b| a.dobles.cmp(&b.dobles),
+        explica: |a, b| Motiu::Localitzadors(Quins::Dobles, a.dobles.clone(), b.dobles.clone()),
+    },
+    Norma {
+        seleccio: "2g",
+        numeracio: "2g",
+        compara: |a, b| b.substituents.len().cmp(&a.substituents.len()),
+        explica: |a, b| Motiu::MesSubstituents(a.substituents.len(), b.substituents.len()),
+    },
+    Norma {
+        seleccio: "2g",
+        numeracio: "3c",
+        compara: |a, b| {
+            a.localitzadors_substituents()
+                .cmp(&b.localitzadors_substituents())
+        },
+        explica: |a, b| {
+            Motiu::Localitzadors(
+                Quins::Substituents,
+                a.localitzadors_substituents(),
+                b.localitzadors_substituents(),
+            )
+        },
+    },
+    Norma {
+        seleccio: "3c",
+        numeracio: "3c",
+        compara: |a, b| {
+            a.localitzadors_alfabetics()
+                .cmp(&b.localitzadors_alfabetics())
+        },
+        explica: |a, b| {
+            Motiu::OrdreAlfabetic(a.localitzadors_alfabetics(), b.localitzadors_alfabetics())
+        },
+    },
+];
+
+/// Les normes (índexs a `NORMES`) que fa servir cada edició, en ordre
+fn normes(edicio: Edition) -> &'static [usize] {
+    match edicio {
+        Edition::Iupac1979 | Edition::Iupac1993 => &[0, 1, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+        Edition::Iupac2013 => &[0, 2, 4, 3, 5, 6, 7, 8, 9, 10, 11],
+    }
+}
+
+/// `Ordering::Less` vol dir que `a` és millor cadena principal que `b`
+fn compara(a: &Candidat, b: &Candidat, edicio: Edition) -> Ordering {
+    normes(edicio)
+        .iter()
+        .map(|&i| (NORMES[i].compara)(a, b))
+        .find(|o| o.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// La norma per la qual `millor` guanya `altre`, si no són equivalents, com a (posició a
+/// `normes`, pas de l'explicació)
+fn norma_decisiva(
+    millor: &Candidat,
+    altre: &Candidat,
+    edicio: Edition,
+    lx: &Lexic,
+) -> Option<(usize, Step)> {
+    let i = normes(edicio)
+        .iter()
+        .position(|&i| (NORMES[i].compara)(millor, altre).is_ne())?;
+    let mateixa_cadena = millor.nodes() == altre.nodes();
+    let norma = &NORMES[normes(edicio)[i]];
+    Some((
+        i,
+        Step {
+            rule: if mateixa_cadena {
+                norma.numeracio
+            } else {
+                norma.seleccio
+            },
+            text: lx.explica(&(norma.explica)(millor, altre)),
+        },
+    ))
+}
+
+fn anomena_graf(g: &Graf, opts: &NamingOptions) -> Result<SystematicName, NamingError> {
+    valida(g)?;
+    let lx = Lexic::de(opts.language);
+
+    // 1. Trio funció principal
+    let principal = GRUPS_PRINCIPALS
+        .iter()
+        .copied()
+        .find(|r| g.nodes.contains(r));
+
+    let mut explicacio = vec![Step {
+        rule: "1",
+        text: lx.explica(&match principal {
+            Some(p) => Motiu::GrupPrincipal(p),
+            None => Motiu::SenseGrupPrincipal,
+        }),
+    }];
+
+    // 2, 3. Trio i numero la cadena principal
+    let candidats: Vec<Candidat> = cadenes(g, principal)
+        .into_iter()
+        .map(|c| Candidat::new(g, lx, c, false, principal))
+        .chain(
+            numeracions_anells(g)
+                .into_iter()
+                .map(|c| Candidat::new(g, lx, c, true, principal)),
+        )
+        .collect();
+    let millor = candidats
+        .iter()
+        .reduce(|m, c| match compara(c, m, opts.edition) {
+            Ordering::Less => c,
+            _ => m,
+        })
+        .expect("there's at least one carbon, so at least one chain");
+    // Una altra cadena es compara amb la seva millor numeració: les pitjors no les ha descartat
+    // cap norma de la 2, sinó la numeració de la mateixa cadena
+    let mut rivals: Vec<&Candidat> = vec![];
+    for c in &candidats {
+        match rivals.iter_mut().find(|r| r.nodes() == c.nodes()) {
+            Some(r) if c.nodes() != millor.nodes() => {
+                if compara(c, r, opts.edition).is_lt() {
+                    *r = c;
+                }
+            }
+            _ => rivals.push(c),
+        }
+    }
+    // Per cada norma que ha decidit res, la primera cadena (o numeració) que ha descartat
+    let mut decisives: Vec<(usize, Step)> = vec![];
+    for c in rivals {
+        if let Some((i, pas)) = norma_decisiva(millor, c, opts.edition, lx)
+            && !decisives.iter().any(|(j, p)| *j == i && p.rule == pas.rule)
+        {
+            decisives.push((i, pas));
+        }
+    }
+    decisives.sort_by_key(|(i, pas)| (*i, pas.rule));
+    if decisives.is_empty() {
+        let una = candidats.iter().all(|c| c.nodes() == millor.nodes());
+        explicacio.push(Step {
+            rule: "2",
+            text: lx.explica(match una {
+                true => &Motiu::UnaCadena,
+                false => &Motiu::CadenesEquivalents,
+            }),
+        });
+    }
+    explicacio.extend(decisives.into_iter().map(|(_, pas)| pas));
+    let substituents = millor
+        .substituents
+        .iter()
+        .map(|(l, nom)| nom.clone().map(|nom| (*l, nom)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let substituents = match opts.short_substituent_names {
+        true => substituents
+            .into_iter()
+            .map(|(l, nom)| (l, nom_curt(lx, &nom).unwrap_or(nom)))
+            .collect(),
+        false => substituents,
+    };
+
+    // 4. Formo el nom
+    let mut localitzadors = if millor.es_anell {
+        localitzadors_anell(millor)
+    } else {
+        Localitzadors::tots(calen_localitzadors(millor, principal))
+    };
+    // Un grup terminal de la cadena és a l'extrem, i no en du: "àcid butandioic"
+    if principal.is_some_and(es_terminal) && !millor.es_anell {
+        localitzadors.principals = false;
+    }
+    localitzadors.davant = opts.edition == Edition::Iupac1979;
+    let sufix = principal.map(|p| match (es_terminal(p), millor.es_anell) {
+        (true, true) => lx.sufix_anell(p),
+        _ => lx.sufix(p),
+    });
+    let nom = if millor.es_benze && millor.principals.len() > 1 {
+        // "benzè-1,2-diol": amb més d'un grup principal no hi ha nom de derivat
+        let mut nom = prefixos(&substituents, localitzadors.substituents);
+        nom.push_str(lx.benze(None).expect("the benzene itself has a name"));
+        let sufix = sufix.expect("there are principal groups");
+        lx.sufixa(
+            &mut nom,
+            &format!("-{}-", llista_localitzadors(&millor.principals)),
+            &format!("{}{sufix}", multiplicador(millor.principals.len())),
+        );
+        nom
+    } else if millor.es_benze {
+        let Some(benze) = lx.benze(principal) else {
+            let grup = principal.expect("the benzene itself has a name");
+            return Err(NamingError::UnsupportedGroup(format!(
+                "derivats del benzè amb {grup}"
+            )));
+        };
+        let mut locs = [&millor.principals[..], &millor.localitzadors_substituents()].concat();
+        locs.sort();
+        match locs[..] {
+            [1, l @ 2..=4] if opts.ortho_meta_para => format!(
+                "{}-{}{}",
+                ["o", "m", "p"][l - 2],
+                prefixos(&substituents, false),
+                benze
+            ),
+            _ => format!(
+                "{}{}",
+                prefixos(&substituents, localitzadors.substituents),
+                benze
+            ),
+        }
+    } else {
+        let prefixos = prefixos(&substituents, localitzadors.substituents);
+        let cadena = nom_cadena(lx, millor, sufix, localitzadors)?;
+        // "4-metil-1-pentè"
+        match !prefixos.is_empty() && cadena.starts_with(|c: char| c.is_ascii_digit()) {
+            true => format!("{prefixos}-{cadena}"),
+            false => format!("{prefixos}{cadena}"),
+        }
     };
+    let nom = match principal {
+        Some(UiRadical::Carboxil) => lx.acid(&nom),
+        Some(UiRadical::Ester) => {
+            let mut alquils = vec![];
+            for ester in (0..g.nodes.len()).filter(|&i| g.nodes[i] == UiRadical::Ester) {
+                let alcoxi = g
+                    .costat_alcoxi(ester)
+                    .expect("esters without their alkyl are acids");
+                alquils.push(nom_alquil(g, lx, alcoxi, ester)?);
+            }
+            // "butandioat de dimetil"
+            alquils.dedup();
+            match &alquils[..] {
+                [alquil] => lx.ester(
+                    &nom,
+                    &format!("{}{alquil}", multiplicador(millor.principals.len())),
+                ),
+                _ => {
+                    return Err(NamingError::UnsupportedGroup(
+                        "èsters amb alquils diferents".to_string(),
+                    ));
+                }
+            }
+        }
+        _ => nom,
+    };
+    // Els èters poden tenir nom de classe funcional, però les parts són les del substitutiu
+    let nom = match nom_eter(g, lx) {
+        Some(eter) if principal.is_none() && opts.functional_class_ethers => eter,
+        _ => nom,
+    };
+
+    explicacio.push(Step {
+        rule: "4",
+        text: lx.explica(&Motiu::Nom(nom.clone(), !substituents.is_empty())),
+    });
+
+    Ok(SystematicName {
+        name: nom,
+        parent: ParentChain {
+            kind: match (millor.es_anell, millor.es_benze) {
+                (false, _) => ParentKind::Chain,
+                (true, false) => ParentKind::Ring,
+                (true, true) => ParentKind::Benzene,
+            },
+            carbons: millor.cadena.len(),
+            double_bonds: millor.dobles.clone(),
+            triple_bonds: millor.triples.clone(),
+        },
+        principal_locants: millor.principals.clone(),
+        suffix: sufix,
+        substituents,
+        explanation: explicacio,
+    })
 }
 
-fn get_adjacent(g: &[UiBlock], index: usize) -> Vec<usize> {
-    let adj_ids = &g[index].links;
-    adj_ids
+/// "etil metil èter", si la molècula és un èter i prou: R-O-R' amb R i R' alquils
+fn nom_eter(g: &Graf, lx: &Lexic) -> Option<String> {
+    let [eter] = (0..g.nodes.len())
+        .filter(|&i| g.nodes[i] == UiRadical::Eter)
+        .collect::<Vec<_>>()[..]
+    else {
+        return None;
+    };
+    let mut alquils = g.adj[eter]
         .iter()
-        .map(|id| g.iter().position(|b| b.id == *id).unwrap())
-        .collect()
+        .map(|(v, _)| nom_alquil(g, lx, *v, eter).ok())
+        .collect::<Option<Vec<_>>>()?;
+    alquils.sort_by_key(|nom| clau_alfabetica(nom));
+    match &alquils[..] {
+        [a, b] if a == b => Some(format!("{}{a} {}", multiplicador(2), lx.eter)),
+        [a, b] => Some(format!("{a} {b} {}", lx.eter)),
+        _ => None,
+    }
 }
 
-/// Ok(nodes) | Err(There's a cycle)
-fn find_connex(g: &[UiBlock], index: usize) -> Result<Vec<UiBlock>, ()> {
-    fn go(g: &[UiBlock], seen: &mut [bool], i: usize, prev: usize) -> bool {
-        // bool is cycle
-        if seen[i] {
-            return true;
+/// Comprova que els grups estiguin enllaçats de manera que els sapiguem anomenar
+fn valida(g: &Graf) -> Result<(), NamingError> {
+    use UiRadical as R;
+    for (i, r) in g.nodes.iter().enumerate() {
+        if g.anells.iter().filter(|a| a.contains(&i)).count() > 1 {
+            return Err(NamingError::Cycle("anells fusionats".to_string()));
         }
-        seen[i] = true;
-        for adj_idx in get_adjacent(g, i).into_iter().filter(|&x| x != prev) {
-            if go(g, seen, adj_idx, i) {
-                return true;
+        if g.anell_de(i).is_some() && !matches!(r, R::C | R::Cetona) {
+            return Err(NamingError::Cycle(format!("anells amb {r}")));
+        }
+        for &(v, m) in &g.adj[i] {
+            let altre = g.nodes[v];
+            if m > 1 && (*r != R::C || altre != R::C) {
+                return Err(NamingError::InvalidValence(format!(
+                    "un enllaç múltiple entre {r} i {altre}"
+                )));
+            }
+            if !r.contains_carbon() && !matches!(altre, R::C | R::Cetona) {
+                return Err(NamingError::InvalidValence(format!(
+                    "un {r} enllaçat a {altre}"
+                )));
             }
         }
-        false
     }
+    Ok(())
+}
 
-    let mut seen = vec![false; g.len()];
-    if go(g, &mut seen, index, index) {
-        Err(())
-    } else {
-        Ok(g.iter()
-            .cloned()
-            .enumerate()
-            .filter_map(|(i, n)| seen[i].then_some(n))
-            .collect())
+/// Els localitzadors es poden ometre si no hi ha cap ambigüitat: quan només hi ha una
+/// característica (substituent, enllaç múltiple o grup principal) i només pot anar a un lloc
+fn calen_localitzadors(c: &Candidat, principal: Option<UiRadical>) -> bool {
+    let n = c.cadena.len();
+    // Un grup terminal ja fixa la numeració, i no du localitzador
+    let terminal = principal.is_some_and(es_terminal);
+    let grups = if terminal { 0 } else { c.principals.len() };
+    let (subs, multiples) = (c.substituents.len(), c.multiples().len());
+
+    if n == 1 || (n == 2 && multiples == 1 && subs + grups <= 1) {
+        return false;
+    }
+    if subs + multiples + grups != 1 {
+        return true;
+    }
+    let posicions = match (terminal, multiples == 1) {
+        (true, false) => n - 1,
+        (true, true) => n - 2,
+        (false, true) => n / 2,
+        (false, false) if principal == Some(UiRadical::Cetona) && grups == 1 => (n - 1) / 2,
+        (false, false) => n.div_ceil(2),
+    };
+    posicions > 1
+}
+
+/// Quins localitzadors s'escriuen al nom
+#[derive(Clone, Copy)]
+struct Localitzadors {
+    substituents: bool,
+    multiples: bool,
+    principals: bool,
+    /// Els primers localitzadors de la cadena van davant de l'arrel, com al 1979
+    davant: bool,
+}
+
+impl Localitzadors {
+    fn tots(amb_localitzadors: bool) -> Self {
+        Self {
+            substituents: amb_localitzadors,
+            multiples: amb_localitzadors,
+            principals: amb_localitzadors,
+            davant: false,
+        }
+    }
+}
+
+/// En un anell, la primera característica sempre és a l'1: si és l'única no du localitzador, i un
+/// únic enllaç múltiple tampoc si no hi ha grup principal ("3-metilciclohexè")
+fn localitzadors_anell(c: &Candidat) -> Localitzadors {
+    let (subs, multiples, grups) = (
+        c.substituents.len(),
+        c.multiples().len(),
+        c.principals.len(),
+    );
+    let tots = subs + multiples + grups > 1;
+    Localitzadors {
+        substituents: tots,
+        multiples: tots && (grups > 0 || multiples > 1),
+        principals: tots,
+        davant: false,
+    }
+}
+
+/// L'arrel amb les insaturacions i el sufix del grup principal, p.e. "pent-2-è", "buta-1,3-diè",
+/// "pent-1-en-4-í" o "but-3-en-2-ol". El sufix va acompanyat dels seus localitzadors, si en du.
+/// Amb `localitzadors.davant`, els primers van davant de tot: "1,3-butadiè", "3-buten-2-ol"
+fn nom_cadena(
+    lx: &Lexic,
+    c: &Candidat,
+    sufix: Option<&str>,
+    localitzadors: Localitzadors,
+) -> Result<String, NamingError> {
+    let n = c.cadena.len();
+    let [an, en, in_] = lx.infixos;
+    // (localitzadors, multiplicador, infix)
+    let mut trossos: Vec<(&[usize], String, usize)> = vec![];
+    if !c.dobles.is_empty() {
+        trossos.push((&c.dobles, multiplicador(c.dobles.len()), 1));
+    }
+    if !c.triples.is_empty() {
+        trossos.push((&c.triples, multiplicador(c.triples.len()), 2));
+    }
+
+    // Els localitzadors que van davant de l'arrel, si n'hi ha
+    let mut davant = None;
+    let mut posa = |s: &mut String, ls: &[usize]| match davant {
+        None if localitzadors.davant => davant = Some(llista_localitzadors(ls)),
+        _ => s.push_str(&format!("-{}-", llista_localitzadors(ls))),
+    };
+
+    let mut s = match c.es_anell {
+        true => format!("{}{}", lx.cicle, arrel(lx, n)?),
+        false => arrel(lx, n)?,
+    };
+    let mut darrer = 0;
+    if trossos.is_empty() {
+        s.push_str(an);
+    }
+    // "a" eufònica: buta-1,3-diè
+    if c.dobles.len() > 1 || (c.dobles.is_empty() && c.triples.len() > 1) {
+        s.push('a');
+    }
+    for (ls, mult, infix) in &trossos {
+        // A l'etè i a l'etí l'enllaç sempre és l'1
+        if localitzadors.multiples && n > 2 {
+            posa(&mut s, ls);
+        }
+        s.push_str(mult);
+        s.push_str([an, en, in_][*infix]);
+        darrer = *infix;
+    }
+
+    match sufix {
+        Some(sufix) => {
+            let ls = &c.principals;
+            // En una cadena de dos, un únic grup sempre és a l'1
+            let mut locs = String::new();
+            if localitzadors.principals && !ls.is_empty() && !(n <= 2 && ls.len() == 1) {
+                posa(&mut locs, ls);
+            }
+            // "etan-1,2-diol", "butandioic"
+            lx.sufixa(
+                &mut s,
+                &locs,
+                &format!("{}{sufix}", multiplicador(ls.len())),
+            );
+        }
+        None => {
+            // La darrera terminació canvia: "an" -> "à", "en" -> "è", "in" -> "í"
+            s.truncate(s.len() - lx.infixos[darrer].len());
+            s.push_str(lx.finals[darrer]);
+        }
+    }
+    Ok(match davant {
+        Some(ls) => format!("{ls}-{s}"),
+        None => s,
+    })
+}
+
+/// Totes les cadenes (camins simples) de carbonis, en els dos sentits
+fn cadenes(g: &Graf, principal: Option<UiRadical>) -> Vec<Vec<usize>> {
+    let es_de_cadena = |i: usize| match g.nodes[i] {
+        _ if g.anell_de(i).is_some() => false,
+        UiRadical::C | UiRadical::Cetona => true,
+        r => es_terminal(r) && Some(r) == principal,
+    };
+    // Un èster només forma part de la cadena pel costat del carboni
+    let enllac_de_cadena = |a: usize, b: usize| {
+        let per_l_acil = |e: usize, altre: usize| {
+            g.nodes[e] != UiRadical::Ester || g.costat_acil(e) == Some(altre)
+        };
+        es_de_cadena(a) && es_de_cadena(b) && per_l_acil(a, b) && per_l_acil(b, a)
+    };
+    fn go(
+        g: &Graf,
+        enllac_de_cadena: &dyn Fn(usize, usize) -> bool,
+        cami: &mut Vec<usize>,
+        totes: &mut Vec<Vec<usize>>,
+    ) {
+        let ultim = *cami.last().expect("path is never empty");
+        let mut es_extrem = true;
+        for &(v, _) in &g.adj[ultim] {
+            if enllac_de_cadena(ultim, v) && !cami.contains(&v) {
+                es_extrem = false;
+                cami.push(v);
+                go(g, enllac_de_cadena, cami, totes);
+                cami.pop();
+            }
+        }
+        // Allargar una cadena mai la fa pitjor, així que només ens interessen les que acaben a
+        // un extrem
+        if es_extrem {
+            totes.push(cami.clone());
+        }
+    }
+
+    let mut totes = vec![];
+    for i in (0..g.nodes.len()).filter(|&i| es_de_cadena(i)) {
+        let veins = g.adj[i]
+            .iter()
+            .filter(|(v, _)| enllac_de_cadena(i, *v))
+            .count();
+        if veins <= 1 {
+            go(g, &enllac_de_cadena, &mut vec![i], &mut totes);
+        }
+    }
+    totes
+}
+
+/// Cada anell numerat de totes les maneres possibles: començant per qualsevol node i en els dos
+/// sentits
+fn numeracions_anells(g: &Graf) -> Vec<Vec<usize>> {
+    let mut totes = vec![];
+    for anell in &g.anells {
+        let n = anell.len();
+        for inici in 0..n {
+            totes.push((0..n).map(|k| anell[(inici + k) % n]).collect());
+            totes.push((0..n).map(|k| anell[(inici + n - k) % n]).collect());
+        }
+    }
+    totes
+}
+
+/// Nom del que penja de `pare` començant per `inici`, quan no és el grup principal
+fn nom_substituent(g: &Graf, lx: &Lexic, inici: usize, pare: usize) -> Result<String, NamingError> {
+    use UiRadical as R;
+    match g.nodes[inici] {
+        R::C => nom_alquil(g, lx, inici, pare),
+        // Un CO fora de la cadena vol dir que la branca té més coses
+        R::Cetona => Err(NamingError::UnsupportedGroup(format!(
+            "substituents amb {}",
+            R::Cetona
+        ))),
+        R::Eter => {
+            let altre = g.adj[inici]
+                .iter()
+                .map(|(v, _)| *v)
+                .find(|v| *v != pare)
+                .expect("ethers with an empty side are alcohols");
+            nom_alcoxi(g, lx, altre, inici)
+        }
+        R::Ester if g.costat_acil(inici) == Some(pare) => {
+            let alcoxi = g
+                .costat_alcoxi(inici)
+                .expect("esters without their alkyl are acids");
+            Ok(format!(
+                "({}{})",
+                nom_alcoxi(g, lx, alcoxi, inici)?,
+                lx.carbonil
+            ))
+        }
+        R::Ester => {
+            let acil = match g.costat_acil(inici) {
+                Some(acil) => nom_acil(g, lx, acil, inici)?,
+                // Un formiat, H-COO-
+                None => format!("{}ano", arrel(lx, 1)?),
+            };
+            Ok(format!("({acil}{}{})", lx.il, lx.oxi))
+        }
+        // Els grups terminals que no són el principal no entren a la cadena, i el seu carboni
+        // va amb el prefix (formil, carboxi...)
+        r @ (R::F
+        | R::Cl
+        | R::Br
+        | R::I
+        | R::Alcohol
+        | R::Amina
+        | R::Carboxil
+        | R::Amida
+        | R::Nitril
+        | R::Aldehid) => Ok(lx
+            .prefix(r)
+            .expect("halogens and groups have a prefix")
+            .to_string()),
+        r => Err(NamingError::UnsupportedGroup(format!(
+            "{r} com a substituent"
+        ))),
+    }
+}
+
+/// Nom de la branca que penja de `pare` i comença a `inici`
+fn nom_alquil(g: &Graf, lx: &Lexic, inici: usize, pare: usize) -> Result<String, NamingError> {
+    // "3-metilidenheptà": al 2013 la cadena més llarga pot deixar el doble enllaç fora
+    if g.multiplicitat(pare, inici) == 2 && g.anell_de(inici).is_none() {
+        let n = llargada_alquil(g, inici, pare)?;
+        return Ok(format!("{}{}", arrel(lx, n)?, lx.ilide));
+    }
+    let arrel = match g.anell_de(inici) {
+        Some(_) => arrel_anell(g, lx, inici, pare)?,
+        None => match llargada_alquil(g, inici, pare) {
+            Ok(n) => arrel(lx, n)?,
+            Err(_) => return nom_ramificat(g, lx, inici, pare),
+        },
+    };
+    Ok(format!("{arrel}{}", lx.il))
+}
+
+/// Una branca que es torna a ramificar, o que du substituents, com a substituent compost:
+/// "(1-metilpropil)", "(2-cloroetil)". La seva cadena és la més llarga que surt d'`inici` (i
+/// després la de més substituents i localitzadors més baixos), numerada des d'`inici`
+fn nom_ramificat(g: &Graf, lx: &Lexic, inici: usize, pare: usize) -> Result<String, NamingError> {
+    // Un CO o un CHO a l'oxigen d'un èster (un anhídrid) no és cap alquil
+    if g.nodes[inici] != UiRadical::C {
+        return Err(NamingError::UnsupportedGroup(format!(
+            "substituents amb {}",
+            g.nodes[inici]
+        )));
+    }
+    let es_de_cadena = |i: usize| g.nodes[i] == UiRadical::C && g.anell_de(i).is_none();
+    let mut camins = vec![];
+    let mut pila = vec![vec![inici]];
+    while let Some(cami) = pila.pop() {
+        let ultim = *cami.last().expect("path is never empty");
+        let seguents: Vec<usize> = g.adj[ultim]
+            .iter()
+            .map(|(v, _)| *v)
+            .filter(|v| *v != pare && es_de_cadena(*v) && !cami.contains(v))
+            .collect();
+        for &v in &seguents {
+            pila.push([&cami[..], &[v]].concat());
+        }
+        if seguents.is_empty() {
+            camins.push(cami);
+        }
+    }
+
+    let mut opcions = vec![];
+    for cami in camins {
+        let mut substituents = vec![];
+        for (k, &node) in cami.iter().enumerate() {
+            for &(v, m) in &g.adj[node] {
+                if m > 1 {
+                    return Err(NamingError::UnsupportedGroup(
+                        "substituents insaturats".to_string(),
+                    ));
+                }
+                if v != pare && !cami.contains(&v) {
+                    substituents.push((k + 1, nom_substituent(g, lx, v, node)));
+                }
+            }
+        }
+        let mut locs: Vec<usize> = substituents.iter().map(|(l, _)| *l).collect();
+        locs.sort();
+        let mut alfabetics: Vec<(String, usize)> = substituents
+            .iter()
+            .map(|(l, nom)| (nom.as_deref().map(clau_alfabetica).unwrap_or_default(), *l))
+            .collect();
+        alfabetics.sort();
+        let clau = (
+            std::cmp::Reverse(cami.len()),
+            std::cmp::Reverse(locs.len()),
+            locs,
+            alfabetics.into_iter().map(|(_, l)| l).collect::<Vec<_>>(),
+        );
+        opcions.push((clau, cami.len(), substituents));
+    }
+    let (_, n, substituents) = opcions
+        .into_iter()
+        .min_by(|a, b| a.0.cmp(&b.0))
+        .expect("there's at least the path with just `inici`");
+    let substituents = substituents
+        .into_iter()
+        .map(|(l, nom)| nom.map(|nom| (l, nom)))
+        .collect::<Result<Vec<_>, _>>()?;
+    // Un metil només té un lloc on posar-los: "(triclorometil)"
+    Ok(format!(
+        "({}{}{})",
+        prefixos(&substituents, n > 1),
+        arrel(lx, n)?,
+        lx.il
+    ))
+}
+
+/// El nom curt acceptat del substituent compost `nom`, si en té: "isopropil" per "(1-metiletil)"
+fn nom_curt(lx: &Lexic, nom: &str) -> Option<String> {
+    let metil = format!("{}{}", arrel(lx, 1).ok()?, lx.il);
+    NOMS_CURTS
+        .iter()
+        .find_map(|&(prefix, total, cadena, metils)| {
+            let metils: Vec<(usize, String)> = metils.iter().map(|l| (*l, metil.clone())).collect();
+            let sistematic = format!(
+                "({}{}{})",
+                prefixos(&metils, true),
+                arrel(lx, cadena).ok()?,
+                lx.il
+            );
+            if nom != sistematic {
+                return None;
+            }
+            let prefix = if prefix == "terc-" { lx.terc } else { prefix };
+            Some(format!("{prefix}{}{}", arrel(lx, total).ok()?, lx.il))
+        })
+}
+
+/// Com `nom_alquil`, però enllaçat per un oxigen: "metoxi", "etoxi", "pentiloxi"...
+fn nom_alcoxi(g: &Graf, lx: &Lexic, inici: usize, pare: usize) -> Result<String, NamingError> {
+    if g.anell_de(inici).is_some() {
+        return Ok(match arrel_anell(g, lx, inici, pare)? {
+            arrel if arrel == lx.fen => format!("{arrel}{}", lx.oxi),
+            arrel => format!("{arrel}{}{}", lx.il, lx.oxi),
+        });
+    }
+    match llargada_alquil(g, inici, pare)? {
+        n @ 1..=4 => Ok(format!("{}{}", arrel(lx, n)?, lx.oxi)),
+        n => Ok(format!("{}{}{}", arrel(lx, n)?, lx.il, lx.oxi)),
+    }
+}
+
+/// El grup acil d'un èster, sense la terminació: "etano" per a l'acetil (CH3-CO-)
+fn nom_acil(g: &Graf, lx: &Lexic, inici: usize, pare: usize) -> Result<String, NamingError> {
+    // El carboni del COO també compta
+    Ok(format!(
+        "{}ano",
+        arrel(lx, llargada_alquil(g, inici, pare)? + 1)?
+    ))
+}
+
+/// Carbonis d'una branca sense ramificar ni insaturar. Només el primer enllaç, el que la
+/// penja, pot ser doble
+fn llargada_alquil(g: &Graf, inici: usize, pare: usize) -> Result<usize, NamingError> {
+    let mut llargada = 1;
+    let (mut anterior, mut actual) = (pare, inici);
+    loop {
+        if g.nodes[actual] != UiRadical::C {
+            return Err(NamingError::UnsupportedGroup(format!(
+                "substituents amb {}",
+                g.nodes[actual]
+            )));
+        }
+        let maxim = if anterior == pare { 2 } else { 1 };
+        if g.multiplicitat(anterior, actual) > maxim {
+            return Err(NamingError::UnsupportedGroup(
+                "substituents insaturats".to_string(),
+            ));
+        }
+        let seguents: Vec<usize> = g.adj[actual]
+            .iter()
+            .map(|(v, _)| *v)
+            .filter(|v| *v != anterior)
+            .collect();
+        match seguents[..] {
+            [] => break,
+            [seguent] => {
+                (anterior, actual) = (actual, seguent);
+                llargada += 1;
+            }
+            _ => {
+                return Err(NamingError::UnsupportedGroup(
+                    "substituents ramificats".to_string(),
+                ));
+            }
+        }
+    }
+    Ok(llargada)
+}
+
+/// L'arrel d'un anell que penja de `pare`, sense res més enganxat: "ciclohex", o "fen" si és un
+/// benzè
+fn arrel_anell(g: &Graf, lx: &Lexic, inici: usize, pare: usize) -> Result<String, NamingError> {
+    let anell = g.anell_de(inici).expect("only called on rings");
+    let benze = es_benze(g, anell);
+    let net = anell.iter().all(|&i| {
+        g.nodes[i] == UiRadical::C
+            && g.adj[i]
+                .iter()
+                .all(|&(v, m)| (m == 1 || benze) && (anell.contains(&v) || (i, v) == (inici, pare)))
+    });
+    if !net {
+        return Err(NamingError::UnsupportedGroup(
+            "anells substituïts com a substituents".to_string(),
+        ));
+    }
+    if benze {
+        return Ok(lx.fen.to_string());
+    }
+    Ok(format!("{}{}", lx.cicle, arrel(lx, anell.len())?))
+}
+
+/// Sis carbonis en anell amb tres dobles enllaços (que per força van alternats)
+fn es_benze(g: &Graf, anell: &[usize]) -> bool {
+    let n = anell.len();
+    let multiplicitats: Vec<usize> = (0..n)
+        .map(|k| g.multiplicitat(anell[k], anell[(k + 1) % n]))
+        .collect();
+    n == 6
+        && anell.iter().all(|&i| g.nodes[i] == UiRadical::C)
+        && multiplicitats.iter().filter(|m| **m == 2).count() == 3
+        && multiplicitats.iter().all(|m| *m <= 2)
+}
+
+/// Grups que porten el seu propi carboni i només poden anar al final d'una cadena
+fn es_terminal(r: UiRadical) -> bool {
+    use UiRadical as R;
+    matches!(
+        r,
+        R::Carboxil | R::Ester | R::Amida | R::Nitril | R::Aldehid
+    )
+}
+
+fn arrel(lx: &Lexic, n: usize) -> Result<String, NamingError> {
+    match n {
+        1..=4 => Some(lx.arrels[n - 1].to_string()),
+        n => numerals::arrel(n),
+    }
+    .ok_or_else(|| NamingError::UnsupportedGroup(format!("cadenes de {n} carbonis")))
+}
+
+fn multiplicador(n: usize) -> String {
+    numerals::multiplicador(n, false)
+}
+
+/// Els substituents agrupats i en ordre alfabètic, p.e. "3-etil-2,2-dimetil"
+fn prefixos(substituents: &[(usize, String)], amb_localitzadors: bool) -> String {
+    let mut grups: Vec<(&str, Vec<usize>)> = vec![];
+    for (l, nom) in substituents {
+        match grups.iter_mut().find(|(n, _)| n == nom) {
+            Some((_, ls)) => ls.push(*l),
+            None => grups.push((nom, vec![*l])),
+        }
+    }
+    grups.sort_by_key(|(nom, _)| clau_alfabetica(nom));
+
+    let mut s = String::new();
+    for (nom, mut ls) in grups {
+        ls.sort();
+        if amb_localitzadors {
+            if !s.is_empty() {
+                s.push('-');
+            }
+            s.push_str(&llista_localitzadors(&ls));
+            s.push('-');
+        }
+        // "bis(1-metiletil)", "di-terc-butil"
+        s.push_str(&numerals::multiplicador(ls.len(), nom.starts_with('(')));
+        if ls.len() > 1 && nom.contains('-') && !nom.starts_with('(') {
+            s.push('-');
+        }
+        s.push_str(nom);
+    }
+    s
+}
+
+fn llista_localitzadors(ls: &[usize]) -> String {
+    let ls: Vec<String> = ls.iter().map(|l| l.to_string()).collect();
+    ls.join(",")
+}
+
+/// Per ordenar alfabèticament sense que els accents facin nosa. Un substituent compost va per
+/// la primera lletra del seu nom, multiplicadors inclosos, i "sec-" i "terc-" no compten
+fn clau_alfabetica(nom: &str) -> String {
+    let nom = ["sec-", "terc-", "tert-"]
+        .iter()
+        .find_map(|p| nom.strip_prefix(p))
+        .unwrap_or(nom);
+    nom.chars()
+        .filter(|c| !matches!(c, '(' | ')' | '-' | ',') && !c.is_ascii_digit())
+        .map(sense_accent)
+        .collect()
+}
+
+/// El nom en minúscules, sense accents i amb un sol espai entre paraules, per comparar-lo amb
+/// el que escriu l'usuari
+fn normalitza(nom: &str) -> String {
+    nom.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .map(sense_accent)
+        .collect()
+}
+
+fn sense_accent(c: char) -> char {
+    match c.to_lowercase().next().unwrap_or(c) {
+        'à' | 'á' => 'a',
+        'è' | 'é' => 'e',
+        'í' | 'ï' => 'i',
+        'ò' | 'ó' => 'o',
+        'ú' | 'ü' => 'u',
+        '’' => '\'',
+        c => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use UiRadical::*;
+
+    /// El graf dels grups i els enllaços, com a `Molecule::de`
+    fn graf(grups: &[UiRadical], enllacos: &[(usize, usize, usize)]) -> Graf {
+        Graf::new(&Molecule::de(grups, enllacos))
+    }
+
+    /// El nom sistemàtic de la molècula amb les opcions `opts`
+    fn sistematic(m: &Molecule, opts: &NamingOptions) -> String {
+        anomena_graf(&Graf::new(m), opts)
+            .unwrap_or_else(|e| panic!("{e}"))
+            .name
+    }
+
+    /// Com `sistematic`, a partir dels grups i els enllaços
+    fn amb(
+        opts: &NamingOptions,
+        grups: &[UiRadical],
+        enllacos: &[(usize, usize, usize)],
+    ) -> String {
+        sistematic(&Molecule::de(grups, enllacos), opts)
+    }
+
+    /// Com `amb`, amb les opcions per defecte
+    fn nom(grups: &[UiRadical], enllacos: &[(usize, usize, usize)]) -> String {
+        amb(&NamingOptions::default(), grups, enllacos)
+    }
+
+    /// Els noms sistemàtics en català, castellà i anglès
+    fn en(grups: &[UiRadical], enllacos: &[(usize, usize, usize)]) -> [String; 3] {
+        [Language::Catalan, Language::Spanish, Language::English].map(|language| {
+            let opts = NamingOptions {
+                language,
+                ..Default::default()
+            };
+            amb(&opts, grups, enllacos)
+        })
+    }
+
+    /// Els enllaços simples d'una cadena de `n` grups, en ordre
+    fn cadena(n: usize) -> Vec<(usize, usize, usize)> {
+        (1..n).map(|i| (i - 1, i, 1)).collect()
+    }
+
+    #[test]
+    fn alcans() {
+        assert_eq!(nom(&[C], &[]), "metà");
+        assert_eq!(nom(&[C; 5], &cadena(5)), "pentà");
+        // La cadena més llarga no és la dibuixada en línia
+        let dimetilpenta = [
+            (0, 1, 1),
+            (1, 2, 1),
+            (2, 3, 1),
+            (3, 4, 1),
+            (2, 5, 1),
+            (1, 6, 1),
+        ];
+        assert_eq!(nom(&[C; 7], &dimetilpenta), "2,3-dimetilpentà");
+        let metilbuta = [(0, 1, 1), (1, 2, 1), (2, 3, 1), (2, 4, 1)];
+        assert_eq!(nom(&[C; 5], &metilbuta), "2-metilbutà");
+        let mut etilmetilhexa = cadena(6);
+        etilmetilhexa.extend([(2, 6, 1), (6, 7, 1), (1, 8, 1)]);
+        assert_eq!(nom(&[C; 9], &etilmetilhexa), "3-etil-2-metilhexà");
+        assert_eq!(
+            nom(&[C; 5], &[(0, 1, 1), (1, 2, 1), (1, 3, 1), (1, 4, 1)]),
+            "2,2-dimetilpropà"
+        );
+    }
+
+    /// `cadena(n)` amb els ordres d'alguns enllaços canviats, com a (i, ordre) per a l'enllaç
+    /// entre i i i + 1
+    fn insaturada(n: usize, ordres: &[(usize, usize)]) -> Vec<(usize, usize, usize)> {
+        let mut enllacos = cadena(n);
+        for &(i, ordre) in ordres {
+            enllacos[i].2 = ordre;
+        }
+        enllacos
+    }
+
+    #[test]
+    fn alquens_i_alquins() {
+        assert_eq!(nom(&[C; 2], &insaturada(2, &[(0, 2)])), "etè");
+        assert_eq!(nom(&[C; 2], &insaturada(2, &[(0, 3)])), "etí");
+        // Els localitzadors més baixos per al doble enllaç, es dibuixi on es dibuixi
+        assert_eq!(nom(&[C; 5], &insaturada(5, &[(1, 2)])), "pent-2-è");
+        assert_eq!(nom(&[C; 5], &insaturada(5, &[(2, 2)])), "pent-2-è");
+        assert_eq!(
+            nom(&[C; 6], &insaturada(6, &[(0, 2), (2, 2)])),
+            "hexa-1,3-diè"
+        );
+        assert_eq!(
+            nom(&[C; 5], &insaturada(5, &[(0, 2), (3, 3)])),
+            "pent-1-en-4-í"
+        );
+        assert_eq!(nom(&[C; 4], &insaturada(4, &[(0, 3)])), "but-1-í");
+        // La cadena principal és la més insaturada, encara que no sigui la més llarga
+        let etilbute = [(0, 1, 2), (1, 2, 1), (2, 3, 1), (1, 4, 1), (4, 5, 1)];
+        assert_eq!(nom(&[C; 6], &etilbute), "2-etilbut-1-è");
+    }
+
+    #[test]
+    fn grups_principals() {
+        // Els grups amb carboni compten a la cadena
+        assert_eq!(nom(&[Carboxil], &[]), "àcid metanoic");
+        assert_eq!(nom(&[C, C, Carboxil], &cadena(3)), "àcid propanoic");
+        assert_eq!(nom(&[C, C, Amida], &cadena(3)), "propanamida");
+        assert_eq!(nom(&[C, C, Nitril], &cadena(3)), "propanonitril");
+        assert_eq!(nom(&[C, C, Aldehid], &cadena(3)), "propanal");
+        assert_eq!(nom(&[C, Cetona, C, C, C], &cadena(5)), "pentan-2-ona");
+        assert_eq!(
+            nom(&[C, C, C, Alcohol], &[(0, 1, 1), (1, 2, 1), (1, 3, 1)]),
+            "propan-2-ol"
+        );
+        assert_eq!(nom(&[C, C, C, Amina], &cadena(4)), "propan-1-amina");
+        // Un CO amb un enllaç buit és un aldehid
+        assert_eq!(nom(&[C, Cetona], &cadena(2)), "etanal");
+        // El grup principal té els localitzadors més baixos, davant del doble enllaç
+        let enol = [(0, 1, 2), (1, 2, 1), (2, 3, 1), (2, 4, 1)];
+        assert_eq!(nom(&[C, C, C, C, Alcohol], &enol), "but-3-en-2-ol");
+        let metilbutanona = [(0, 1, 1), (1, 2, 1), (2, 3, 1), (2, 4, 1)];
+        assert_eq!(
+            nom(&[C, Cetona, C, C, C], &metilbutanona),
+            "3-metilbutan-2-ona"
+        );
+    }
+
+    #[test]
+    fn prefixos() {
+        // CHO-CH(OH)-CHCl-CH3
+        let hidroxibutanal = [(0, 1, 1), (1, 2, 1), (2, 3, 1), (1, 4, 1), (2, 5, 1)];
+        assert_eq!(
+            nom(&[Aldehid, C, C, C, Alcohol, Cl], &hidroxibutanal),
+            "3-cloro-2-hidroxibutanal"
+        );
+        assert_eq!(
+            nom(&[C, Cl, Cl, Cl], &[(0, 1, 1), (0, 2, 1), (0, 3, 1)]),
+            "triclorometà"
+        );
+        assert_eq!(
+            nom(&[C, C, Br, Cl], &[(0, 1, 2), (0, 2, 1), (0, 3, 1)]),
+            "1-bromo-1-cloroetè"
+        );
+        let difluoro = [(0, 1, 1), (1, 2, 1), (0, 3, 1), (0, 4, 1), (2, 5, 1)];
+        assert_eq!(
+            nom(&[C, C, C, F, F, Cl], &difluoro),
+            "3-cloro-1,1-difluoropropà"
+        );
+        // Els grups que no són el principal
+        let acid = |r: UiRadical| nom(&[Carboxil, C, C, r], &cadena(4));
+        assert_eq!(acid(Alcohol), "àcid 3-hidroxipropanoic");
+        assert_eq!(acid(Amina), "àcid 3-aminopropanoic");
+        assert_eq!(acid(Nitril), "àcid 3-cianopropanoic");
+        assert_eq!(acid(Aldehid), "àcid 3-formilpropanoic");
+        assert_eq!(acid(Amida), "àcid 3-carbamoïlpropanoic");
+        assert_eq!(
+            nom(&[Carboxil, C, Cetona, C], &cadena(4)),
+            "àcid 3-oxobutanoic"
+        );
+    }
+
+    #[test]
+    fn esters() {
+        assert_eq!(nom(&[C, Ester, C], &cadena(3)), "etanoat de metil");
+        assert_eq!(nom(&[C, Ester, C, C], &cadena(4)), "etanoat d'etil");
+        assert_eq!(nom(&[C, C, Ester, C], &cadena(4)), "propanoat de metil");
+        // Sense res a l'oxigen és un àcid
+        assert_eq!(nom(&[C, Ester], &cadena(2)), "àcid etanoic");
+        // Com a substituents, pels dos costats
+        assert_eq!(
+            nom(&[Carboxil, C, Ester, C], &cadena(4)),
+            "àcid (metoxicarbonil)etanoic"
+        );
+        assert_eq!(
+            nom(&[C, Ester, C, Carboxil], &cadena(4)),
+            "àcid (etanoiloxi)etanoic"
+        );
+        // Els formiats no tenen res al costat del carboni
+        let opts = NamingOptions::default();
+        let formiat = Molecule::from_links(&[Ester, C], &[((0, 1), (1, 0))]);
+        assert_eq!(sistematic(&formiat, &opts), "metanoat de metil");
+        let formiloxi =
+            Molecule::from_links(&[Ester, C, Carboxil], &[((0, 1), (1, 0)), ((1, 1), (2, 0))]);
+        assert_eq!(sistematic(&formiloxi, &opts), "àcid (metanoiloxi)etanoic");
+    }
+
+    #[test]
+    fn ports() {
+        assert_eq!(Ester.port_labels(), Some(&["C", "O"][..]));
+        assert_eq!(C.port_labels(), None);
+        // El metil va a l'oxigen (port 1) encara que s'hagi enllaçat primer
+        let girat = Molecule::from_links(
+            &[C, Ester, C, C],
+            &[((0, 0), (1, 1)), ((1, 0), (2, 0)), ((2, 1), (3, 0))],
+        );
+        let g = Graf::new(&girat);
+        assert_eq!(g.costat_acil(1), Some(2));
+        assert_eq!(g.costat_alcoxi(1), Some(0));
+        assert_eq!(
+            sistematic(&girat, &NamingOptions::default()),
+            "propanoat de metil"
+        );
+    }
+
+    #[test]
+    fn eters() {
+        assert_eq!(nom(&[C, Eter, C, C], &cadena(4)), "metoxietà");
+        assert_eq!(nom(&[C, C, Eter, C, C, C], &cadena(6)), "1-etoxipropà");
+        assert_eq!(nom(&[C, Eter, C, C, Alcohol], &cadena(5)), "2-metoxietanol");
+
+        let classe_funcional = NamingOptions {
+            functional_class_ethers: true,
+            ..Default::default()
+        };
+        let eter = |grups: &[UiRadical]| amb(&classe_funcional, grups, &cadena(grups.len()));
+        assert_eq!(eter(&[C, Eter, C, C]), "etil metil èter");
+        assert_eq!(eter(&[C, C, Eter, C, C]), "dietil èter");
+        // Quan hi ha un grup principal, l'èter va com a prefix
+        assert_eq!(eter(&[C, Eter, C, C, Alcohol]), "2-metoxietanol");
+    }
+
+    /// Els enllaços d'un anell de `n` grups, més els d'`altres`
+    fn anell(n: usize, altres: &[(usize, usize, usize)]) -> Vec<(usize, usize, usize)> {
+        let mut enllacos: Vec<_> = (0..n).map(|i| (i, (i + 1) % n, 1)).collect();
+        enllacos.extend_from_slice(altres);
+        enllacos
+    }
+
+    #[test]
+    fn cicles() {
+        assert_eq!(nom(&[C; 6], &anell(6, &[])), "ciclohexà");
+        assert_eq!(nom(&[C; 6], &anell(5, &[(2, 5, 1)])), "metilciclopentà");
+        assert_eq!(
+            nom(&[C; 8], &anell(6, &[(0, 6, 1), (2, 7, 1)])),
+            "1,3-dimetilciclohexà"
+        );
+        let mut ciclohexe = anell(6, &[]);
+        ciclohexe[2].2 = 2;
+        assert_eq!(nom(&[C; 6], &ciclohexe), "ciclohexè");
+        let mut grups = vec![C; 6];
+        grups.push(Alcohol);
+        assert_eq!(nom(&grups, &anell(6, &[(4, 6, 1)])), "ciclohexanol");
+        grups[6] = Carboxil;
+        assert_eq!(
+            nom(&grups, &anell(6, &[(1, 6, 1)])),
+            "àcid ciclohexancarboxílic"
+        );
+
+        // Entre l'anell i la cadena, el que tengui més carbonis; si empaten, l'anell
+        let hexil = anell(
+            3,
+            &[
+                (0, 3, 1),
+                (3, 4, 1),
+                (4, 5, 1),
+                (5, 6, 1),
+                (6, 7, 1),
+                (7, 8, 1),
+            ],
+        );
+        assert_eq!(nom(&[C; 9], &hexil), "1-ciclopropilhexà");
+        let propil = anell(3, &[(0, 3, 1), (3, 4, 1), (4, 5, 1)]);
+        assert_eq!(nom(&[C; 6], &propil), "propilciclopropà");
+        // Una cetona que penja de l'anell va a la cadena, sigui quin sigui el seu port
+        let mut grups = vec![C; 6];
+        grups.extend([Cetona, C]);
+        assert_eq!(
+            nom(&grups, &anell(6, &[(0, 6, 1), (6, 7, 1)])),
+            "1-ciclohexiletanona"
+        );
+        assert_eq!(
+            nom(&grups, &anell(6, &[(6, 7, 1), (0, 6, 1)])),
+            "1-ciclohexiletanona"
+        );
+
+        // Els anells fusionats encara no
+        let decali = anell(6, &[(0, 6, 1), (6, 7, 1), (7, 8, 1), (8, 9, 1), (9, 1, 1)]);
+        assert!(matches!(
+            anomena_graf(&graf(&[C; 10], &decali), &NamingOptions::default()),
+            Err(NamingError::Cycle(_))
+        ));
+    }
+
+    /// Un benzè amb els grups als seus carbonis, com a (carboni, grup)
+    fn benze(substituents: &[(usize, UiRadical)]) -> Molecule {
+        let mut grups = vec![Benze];
+        let mut enllacos = vec![];
+        for &(carboni, r) in substituents {
+            enllacos.push(((0, carboni), (grups.len(), 0)));
+            grups.push(r);
+        }
+        Molecule::from_links(&grups, &enllacos)
+    }
+
+    #[test]
+    fn benzens() {
+        let d = NamingOptions::default();
+        let omp = NamingOptions {
+            ortho_meta_para: true,
+            ..d
+        };
+        assert_eq!(sistematic(&benze(&[]), &d), "benzè");
+        assert_eq!(sistematic(&benze(&[(3, C)]), &d), "metilbenzè");
+        assert_eq!(
+            sistematic(&benze(&[(5, Cl), (0, Cl)]), &d),
+            "1,2-diclorobenzè"
+        );
+        assert_eq!(
+            sistematic(&benze(&[(0, Cl), (2, Cl)]), &d),
+            "1,3-diclorobenzè"
+        );
+        assert_eq!(
+            sistematic(&benze(&[(1, Cl), (4, Cl)]), &d),
+            "1,4-diclorobenzè"
+        );
+        assert_eq!(
+            sistematic(&benze(&[(5, Cl), (0, Cl)]), &omp),
+            "o-diclorobenzè"
+        );
+        assert_eq!(
+            sistematic(&benze(&[(0, Cl), (2, Cl)]), &omp),
+            "m-diclorobenzè"
+        );
+        assert_eq!(
+            sistematic(&benze(&[(1, Cl), (4, Cl)]), &omp),
+            "p-diclorobenzè"
+        );
+        assert_eq!(sistematic(&benze(&[(2, Alcohol)]), &d), "fenol");
+        assert_eq!(
+            sistematic(&benze(&[(2, Alcohol), (5, C)]), &d),
+            "4-metilfenol"
+        );
+        assert_eq!(
+            sistematic(&benze(&[(2, Alcohol), (5, C)]), &omp),
+            "p-metilfenol"
+        );
+        assert_eq!(sistematic(&benze(&[(2, Carboxil)]), &d), "àcid benzoic");
+        let acetofenona =
+            Molecule::from_links(&[Benze, Cetona, C], &[((0, 0), (1, 0)), ((1, 1), (2, 0))]);
+        assert_eq!(sistematic(&acetofenona, &d), "1-feniletanona");
+        let benzofenona = Molecule::from_links(
+            &[Benze, Cetona, Benze],
+            &[((0, 0), (1, 0)), ((1, 1), (2, 0))],
+        );
+        assert_eq!(sistematic(&benzofenona, &d), "difenilmetanona");
+        // La cetona no fa cap derivat del benzè amb nom propi
+        assert_eq!(Lexic::de(Language::Catalan).benze(Some(Cetona)), None);
+        // Dibuixat amb carbonis sols, amb els dobles enllaços alternats
+        let kekule = [
+            (0, 1, 2),
+            (1, 2, 1),
+            (2, 3, 2),
+            (3, 4, 1),
+            (4, 5, 2),
+            (5, 0, 1),
+        ];
+        assert_eq!(nom(&[C; 6], &kekule), "benzè");
+    }
+
+    #[test]
+    fn noms_propis() {
+        let propi = |m: &Molecule| noms_propis::nom_propi(m, Language::Catalan);
+        assert_eq!(
+            propi(&Molecule::de(&[C, Cetona, C], &cadena(3))),
+            Some("acetona")
+        );
+        assert_eq!(propi(&Molecule::de(&[Cetona], &[])), Some("formaldehid"));
+        // Amb el bloc de l'èster i l'oxigen buit
+        assert_eq!(
+            propi(&Molecule::de(&[C, Ester], &cadena(2))),
+            Some("àcid acètic")
+        );
+        // Amb blocs d'èter en lloc d'alcohols
+        let glicerina = Molecule::de(
+            &[C, C, C, Eter, Eter, Eter],
+            &[(0, 1, 1), (1, 2, 1), (0, 3, 1), (1, 4, 1), (2, 5, 1)],
+        );
+        assert_eq!(propi(&glicerina), Some("glicerina"));
+        assert_eq!(propi(&benze(&[(4, C)])), Some("toluè"));
+        assert_eq!(propi(&benze(&[(5, C), (2, C)])), Some("p-xilè"));
+        assert_eq!(propi(&benze(&[(5, C), (1, C)])), Some("m-xilè"));
+        let acetat = Molecule::de(&[C, Ester, C, C], &cadena(4));
+        assert_eq!(propi(&acetat), Some("acetat d'etil"));
+        // El mateix acetat d'etil, enllaçant primer l'etil al port de l'oxigen
+        let girat = Molecule::from_links(
+            &[C, Ester, C, C],
+            &[((2, 1), (3, 0)), ((1, 1), (2, 0)), ((0, 0), (1, 0))],
+        );
+        assert_eq!(propi(&girat), Some("acetat d'etil"));
+        assert_eq!(
+            noms_propis::nom_propi(&girat, Language::English),
+            Some("ethyl acetate")
+        );
+        // Amb el carboni de més a l'altre costat de l'èster és un altre compost, sense nom propi
+        assert_eq!(propi(&Molecule::de(&[C, C, Ester, C], &cadena(4))), None);
+        let kekule = Molecule::de(
+            &[C; 7],
+            &[
+                (0, 1, 2),
+                (1, 2, 1),
+                (2, 3, 2),
+                (3, 4, 1),
+                (4, 5, 2),
+                (5, 0, 1),
+                (1, 6, 1),
+            ],
+        );
+        assert_eq!(propi(&kekule), Some("toluè"));
+
+        // Per defecte, el nom és el sistemàtic
+        let nom_amb = |m: &Molecule, trivial_names| {
+            let opts = NamingOptions {
+                trivial_names,
+                ..Default::default()
+            };
+            anomena_molecula(m, &opts).unwrap().name
+        };
+        let acetona = Molecule::de(&[C, Cetona, C], &cadena(3));
+        assert_eq!(nom_amb(&acetona, TrivialNames::Hidden), "propanona");
+        assert_eq!(
+            nom_amb(&acetona, TrivialNames::Alongside),
+            "propanona (acetona)"
+        );
+        assert_eq!(nom_amb(&acetona, TrivialNames::Instead), "acetona");
+        let etile = Molecule::de(&[C, C], &[(0, 1, 2)]);
+        assert_eq!(nom_amb(&etile, TrivialNames::Hidden), "etè");
+        let n = anomena_molecula(&etile, &NamingOptions::default()).unwrap();
+        assert_eq!(n.trivial_name, Some("etilè"));
+    }
+
+    #[test]
+    fn nom_per_parts() {
+        // CH3-CH(OH)-CH(CH3)-CH3
+        let m = Molecule::de(
+            &[C, C, C, C, Alcohol, C],
+            &[(0, 1, 1), (1, 2, 1), (2, 3, 1), (1, 4, 1), (2, 5, 1)],
+        );
+        let n = anomena_molecula(&m, &NamingOptions::default()).unwrap();
+        assert_eq!(n.name, "3-metilbutan-2-ol");
+        assert_eq!(n.trivial_name, None);
+        let s = n.systematic.unwrap();
+        let cadena = ParentChain {
+            kind: ParentKind::Chain,
+            carbons: 4,
+            double_bonds: vec![],
+            triple_bonds: vec![],
+        };
+        assert_eq!(s.parent, cadena);
+        assert_eq!(s.principal_locants, [2]);
+        assert_eq!(s.substituents, [(3, "metil".to_string())]);
+        assert_eq!(s.suffix, Some("ol"));
+        assert!(!s.explanation.is_empty());
+    }
+
+    #[test]
+    fn errors() {
+        let error = |grups: &[UiRadical], enllacos: &[(usize, usize, usize)]| {
+            anomena_graf(&graf(grups, enllacos), &NamingOptions::default()).unwrap_err()
+        };
+        let doble = error(&[C, C, C, Eter], &[(0, 1, 1), (1, 2, 1), (2, 3, 2)]);
+        assert!(matches!(doble, NamingError::InvalidValence(_)));
+        assert_eq!(
+            doble.to_string(),
+            "No sé anomenar un enllaç múltiple entre C i O"
+        );
+        assert!(matches!(
+            error(&[C, C, Eter], &[(0, 1, 1), (1, 2, 1), (2, 0, 1)]),
+            NamingError::Cycle(_)
+        ));
+        // Un metilciclopropil penjant d'un hexà
+        let mut metilciclopropilhexa = cadena(6);
+        metilciclopropilhexa.extend([(0, 6, 1), (6, 7, 1), (7, 8, 1), (8, 6, 1), (7, 9, 1)]);
+        assert_eq!(
+            error(&[C; 10], &metilciclopropilhexa).to_string(),
+            "Encara no sé anomenar anells substituïts com a substituents"
+        );
+        let molecula = |grups: &[UiRadical], enllacos: &[(usize, usize, usize)]| {
+            anomena_molecula(&Molecule::de(grups, enllacos), &NamingOptions::default()).unwrap_err()
+        };
+        assert_eq!(molecula(&[Cl, Amina], &[(0, 1, 1)]), NamingError::NoCarbon);
+        assert_eq!(molecula(&[C, C], &[]), NamingError::Disconnected);
+        // La trimetilamina: l'N no fa cap grup
+        assert!(matches!(
+            molecula(&[C, N, C, C], &[(0, 1, 1), (1, 2, 1), (1, 3, 1)]),
+            NamingError::UnsupportedGroup(_)
+        ));
+        // Més enllaços dels que es poden fer: un C amb cinc, un enllaç quàdruple i un H amb dos
+        let pentavalent = molecula(
+            &[C; 6],
+            &[(0, 1, 1), (0, 2, 1), (0, 3, 1), (0, 4, 1), (0, 5, 1)],
+        );
+        assert_eq!(
+            pentavalent,
+            NamingError::InvalidValence("un C amb 5 enllaços".to_string())
+        );
+        assert_eq!(
+            pentavalent.to_string(),
+            "No sé anomenar un C amb 5 enllaços"
+        );
+        assert_eq!(
+            molecula(&[C, C], &[(0, 1, 4)]),
+            NamingError::InvalidValence("un enllaç d'ordre 4 entre C i C".to_string())
+        );
+        assert_eq!(
+            molecula(&[C, H, C], &cadena(3)),
+            NamingError::InvalidValence("un H amb 2 enllaços".to_string())
+        );
+        // Els anhídrids, acètic i acètic fòrmic, no són èsters d'un alquil
+        assert_eq!(
+            error(&[C, Ester, Cetona, C], &cadena(4)),
+            NamingError::UnsupportedGroup("substituents amb CO".to_string())
+        );
+        assert_eq!(
+            error(&[C, Ester, Aldehid], &cadena(3)),
+            NamingError::UnsupportedGroup("substituents amb CHO".to_string())
+        );
+    }
+
+    #[test]
+    fn idiomes() {
+        assert_eq!(en(&[C], &[]), ["metà", "metano", "methane"]);
+        assert_eq!(
+            en(&[C, C, Ester, C, C], &cadena(5)),
+            [
+                "propanoat d'etil",
+                "propanoato de etilo",
+                "ethyl propanoate"
+            ]
+        );
+        assert_eq!(
+            en(&[C, C, Nitril], &cadena(3)),
+            ["propanonitril", "propanonitrilo", "propanenitrile"]
+        );
+        assert_eq!(
+            en(&[C, Carboxil], &cadena(2)),
+            ["àcid etanoic", "ácido etanoico", "ethanoic acid"]
+        );
+        let inol = [(0, 1, 3), (1, 2, 1), (2, 3, 1), (2, 4, 1)];
+        assert_eq!(
+            en(&[C, C, C, C, Alcohol], &inol),
+            ["but-3-in-2-ol", "but-3-in-2-ol", "but-3-yn-2-ol"]
+        );
+        assert_eq!(
+            en(&[Benze, Cl], &cadena(2)),
+            ["clorobenzè", "clorobenceno", "chlorobenzene"]
+        );
+        assert_eq!(
+            en(&[C; 6], &anell(6, &[])),
+            ["ciclohexà", "ciclohexano", "cyclohexane"]
+        );
+
+        let eter = NamingOptions {
+            language: Language::English,
+            functional_class_ethers: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            amb(&eter, &[C, Eter, C, C], &cadena(4)),
+            "ethyl methyl ether"
+        );
+    }
+
+    #[test]
+    fn explicacio() {
+        let d = NamingOptions::default();
+        // El 2,3-dimetilpentà, dibuixat amb quatre carbonis en línia
+        let dimetilpenta = [
+            (0, 1, 1),
+            (1, 2, 1),
+            (2, 3, 1),
+            (3, 4, 1),
+            (2, 5, 1),
+            (1, 6, 1),
+        ];
+        let passos = anomena_graf(&graf(&[C; 7], &dimetilpenta), &d)
+            .unwrap()
+            .explanation;
+        let normes: Vec<&str> = passos.iter().map(|p| p.rule).collect();
+        assert_eq!(normes, ["1", "2d", "3c", "4"]);
+        assert_eq!(
+            passos[1].to_string(),
+            "2d: s'escull la cadena de 5 carbonis davant la de 4 carbonis"
+        );
+        assert_eq!(
+            passos[2].to_string(),
+            "3c: localitzadors més baixos per als substituents: 2,3 davant 3,4"
+        );
+        for (language, passos_) in [
+            (
+                Language::Spanish,
+                [
+                    "1: no hay grupo principal",
+                    "2d: se escoge la cadena de 5 carbonos frente a la de 4 carbonos",
+                    "3c: localizadores más bajos para los sustituyentes: 2,3 frente a 3,4",
+                    "4: con los sustituyentes en orden alfabético delante, el nombre es \
+                     2,3-dimetilpentano",
+                ],
+            ),
+            (
+                Language::English,
+                [
+                    "1: there is no principal group",
+                    "2d: chose the 5-carbon chain over the 4-carbon one",
+                    "3c: lowest locants for the substituents: 2,3 over 3,4",
+                    "4: with the substituents in alphabetical order in front, the name is \
+                     2,3-dimethylpentane",
+                ],
+            ),
+        ] {
+            let opts = NamingOptions {
+                language,
+                ..Default::default()
+            };
+            let passos = anomena_graf(&graf(&[C; 7], &dimetilpenta), &opts)
+                .unwrap()
+                .explanation;
+            let passos: Vec<String> = passos.iter().map(Step::to_string).collect();
+            assert_eq!(passos, passos_);
+        }
+
+        // Un carboni sol: "d'1 carboni"
+        let metilciclopropa = anell(3, &[(0, 3, 1)]);
+        let passos = anomena_graf(&graf(&[C; 4], &metilciclopropa), &d)
+            .unwrap()
+            .explanation;
+        assert_eq!(
+            passos[1].to_string(),
+            "2d: s'escull l'anell de 3 carbonis davant la cadena d'1 carboni"
+        );
+
+        let enol = [(0, 1, 2), (1, 2, 1), (2, 3, 1), (2, 4, 1)];
+        let passos = anomena_graf(&graf(&[C, C, C, C, Alcohol], &enol), &d)
+            .unwrap()
+            .explanation;
+        assert_eq!(passos[0].to_string(), "1: el grup principal és l'OH (-ol)");
+        assert_eq!(
+            passos[1].to_string(),
+            "3a: localitzadors més baixos per als grups principals: 2 davant 3"
+        );
+        let acid = anomena_graf(&graf(&[C, C, Carboxil], &cadena(3)), &d)
+            .unwrap()
+            .explanation;
+        assert_eq!(
+            acid[0].to_string(),
+            "1: el grup principal és el COOH (-oic)"
+        );
+
+        let propil = anell(3, &[(0, 3, 1), (3, 4, 1), (4, 5, 1)]);
+        let passos = anomena_graf(&graf(&[C; 6], &propil), &d)
+            .unwrap()
+            .explanation;
+        assert_eq!(
+            passos[1].to_string(),
+            "2d: a igualtat de carbonis, s'escull l'anell davant la cadena"
+        );
+    }
+
+    #[test]
+    fn cadenes_llargues() {
+        for (n, nom_) in [
+            (11, "undecà"),
+            (12, "dodecà"),
+            (20, "icosà"),
+            (21, "henicosà"),
+            (22, "docosà"),
+            (30, "triacontà"),
+            (100, "hectà"),
+        ] {
+            assert_eq!(nom(&vec![C; n], &cadena(n)), nom_);
+        }
+        let mut metilundeca = cadena(11);
+        metilundeca.push((1, 11, 1));
+        assert_eq!(nom(&[C; 12], &metilundeca), "2-metilundecà");
+        assert_eq!(
+            en(&[C; 22], &cadena(22)),
+            ["docosà", "docosano", "docosane"]
+        );
+    }
+
+    #[test]
+    fn substituents_ramificats() {
+        let curts = NamingOptions {
+            short_substituent_names: true,
+            ..Default::default()
+        };
+        // Una cadena de `n` carbonis amb branques de carbonis
+        let ramificada = |n: usize, branques: &[(usize, usize)]| {
+            let mut enllacos = cadena(n);
+            enllacos.extend(branques.iter().map(|&(a, b)| (a, b, 1)));
+            Molecule::de(&vec![C; n + branques.len()], &enllacos)
+        };
+        // (molècula, nom llarg, nom curt)
+        for (m, llarg, curt) in [
+            (
+                ramificada(7, &[(3, 7), (7, 8), (7, 9)]),
+                "4-(1-metiletil)heptà",
+                "4-isopropilheptà",
+            ),
+            (
+                ramificada(9, &[(4, 9), (9, 10), (9, 11), (9, 12)]),
+                "5-(1,1-dimetiletil)nonà",
+                "5-terc-butilnonà",
+            ),
+            (
+                ramificada(9, &[(4, 9), (9, 10), (10, 11), (10, 12)]),
+                "5-(2-metilpropil)nonà",
+                "5-isobutilnonà",
+            ),
+            (
+                ramificada(9, &[(4, 9), (9, 10), (10, 11), (9, 12)]),
+                "5-(1-metilpropil)nonà",
+                "5-sec-butilnonà",
+            ),
+            (
+                ramificada(8, &[(3, 8), (8, 9), (8, 10), (4, 11), (11, 12), (11, 13)]),
+                "4,5-bis(1-metiletil)octà",
+                "4,5-diisopropiloctà",
+            ),
+        ] {
+            assert_eq!(sistematic(&m, &NamingOptions::default()), llarg);
+            assert_eq!(sistematic(&m, &curts), curt);
+        }
+    }
+
+    #[test]
+    fn grups_principals_repetits() {
+        assert_eq!(
+            en(&[Alcohol, C, C, Alcohol], &cadena(4)),
+            ["etan-1,2-diol", "etano-1,2-diol", "ethane-1,2-diol"]
+        );
+        assert_eq!(
+            en(&[Carboxil, C, C, Carboxil], &cadena(4)),
+            ["àcid butandioic", "ácido butanodioico", "butanedioic acid"]
+        );
+        assert_eq!(
+            en(&[C, Cetona, C, Cetona, C, C], &cadena(6)),
+            ["hexan-2,4-diona", "hexano-2,4-diona", "hexane-2,4-dione"]
+        );
+    }
+
+    #[test]
+    fn edicions() {
+        let edicio = |edition, language| NamingOptions {
+            language,
+            edition,
+            ..Default::default()
+        };
+        let de_1979 = edicio(Edition::Iupac1979, Language::Catalan);
+        let butanol = [(0, 1, 1), (1, 2, 1), (2, 3, 1), (1, 4, 1)];
+        assert_eq!(nom(&[C, C, C, C, Alcohol], &butanol), "butan-2-ol");
+        assert_eq!(amb(&de_1979, &[C, C, C, C, Alcohol], &butanol), "2-butanol");
+        assert_eq!(
+            amb(
+                &edicio(Edition::Iupac1979, Language::Spanish),
+                &[C, C, C, C, Alcohol],
+                &butanol
+            ),
+            "2-butanol"
+        );
+        assert_eq!(
+            amb(&de_1979, &[C; 4], &insaturada(4, &[(0, 2), (2, 2)])),
+            "1,3-butadiè"
+        );
+        let butenol = [(0, 1, 1), (1, 2, 1), (2, 3, 2), (1, 4, 1)];
+        assert_eq!(
+            amb(&de_1979, &[C, C, C, C, Alcohol], &butenol),
+            "3-buten-2-ol"
+        );
+        let mut metilpente = insaturada(5, &[(0, 2)]);
+        metilpente.push((3, 5, 1));
+        assert_eq!(amb(&de_1979, &[C; 6], &metilpente), "4-metil-1-pentè");
+        let idiomes = [Language::Catalan, Language::Spanish, Language::English];
+        assert_eq!(
+            idiomes.map(|language| amb(
+                &edicio(Edition::Iupac1979, language),
+                &[Alcohol, C, C, Alcohol],
+                &cadena(4)
+            )),
+            ["1,2-etandiol", "1,2-etanodiol", "1,2-ethanediol"]
+        );
+        // El 2013 la cadena més llarga guanya la que té el doble enllaç
+        let metiliden = [
+            (0, 1, 2),
+            (1, 2, 1),
+            (2, 3, 1),
+            (1, 4, 1),
+            (4, 5, 1),
+            (5, 6, 1),
+            (6, 7, 1),
+        ];
+        assert_eq!(nom(&[C; 8], &metiliden), "2-etilhex-1-è");
+        assert_eq!(
+            idiomes.map(|language| amb(&edicio(Edition::Iupac2013, language), &[C; 8], &metiliden)),
+            [
+                "3-metilidenheptà",
+                "3-metilidenoheptano",
+                "3-methylideneheptane"
+            ]
+        );
     }
 }
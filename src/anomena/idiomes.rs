@@ -0,0 +1,490 @@
+//! Les paraules de cada idioma. Tots tres formen els noms igual, i només canvien les paraules,
+//! les terminacions i on van "àcid" i l'alquil dels èsters
+use super::llista_localitzadors;
+use crate::{Language, UiRadical as R};
+
+pub(super) struct Lexic {
+    pub idioma: Language,
+    /// L'arrel de les cadenes d'1 a 4 carbonis. Les altres són numerals, iguals a tots els idiomes
+    pub arrels: [&'static str; 4],
+    /// Els infixos de l'alcà, l'alquè i l'alquí: "an", "en", "in"
+    pub infixos: [&'static str; 3],
+    /// Amb què es reemplaça el darrer infix quan no hi ha sufix: "à", "è", "í"
+    pub finals: [&'static str; 3],
+    /// (grup, sufix, sufix quan penja d'un anell, prefix quan és substituent)
+    pub grups: [(R, &'static str, &'static str, &'static str); 8],
+    pub halogens: [(R, &'static str); 4],
+    pub il: &'static str,
+    /// El d'un alquil que penja amb un doble enllaç: "metiliden"
+    pub ilide: &'static str,
+    pub oxi: &'static str,
+    pub carbonil: &'static str,
+    pub cicle: &'static str,
+    /// L'arrel del fenil
+    pub fen: &'static str,
+    pub eter: &'static str,
+    /// El prefix de "terc-butil"
+    pub terc: &'static str,
+    /// (grup principal, nom) dels derivats del benzè, i `None` pel benzè mateix
+    pub benzens: [(Option<R>, &'static str); 8],
+}
+
+/// Què ha decidit cada pas de l'explicació d'un nom, perquè `Lexic::explica` ho digui en
+/// l'idioma del nom. Els nombres són els del guanyador i després els de l'altre
+pub(super) enum Motiu {
+    GrupPrincipal(R),
+    SenseGrupPrincipal,
+    UnaCadena,
+    CadenesEquivalents,
+    MesPrincipals(usize, usize),
+    /// L'anell o la cadena amb més carbonis, segons si guanya l'anell
+    AnellOCadena(bool, usize, usize),
+    /// L'anell davant la cadena, sempre (2013)
+    Anell,
+    MesInsaturada(usize, usize),
+    MesLlarga(usize, usize),
+    MesDobles(usize, usize),
+    MesSubstituents(usize, usize),
+    Localitzadors(Quins, Vec<usize>, Vec<usize>),
+    OrdreAlfabetic(Vec<usize>, Vec<usize>),
+    /// El nom, i si du substituents
+    Nom(String, bool),
+}
+
+/// De què són els localitzadors que decideixen
+pub(super) enum Quins {
+    Principals,
+    Multiples,
+    Dobles,
+    Substituents,
+}
+
+pub(super) const CATALA: Lexic = Lexic {
+    idioma: Language::Catalan,
+    arrels: ["met", "et", "prop", "but"],
+    infixos: ["an", "en", "in"],
+    finals: ["à", "è", "í"],
+    grups: [
+        (R::Carboxil, "oic", "carboxílic", "carboxi"),
+        (R::Ester, "oat", "carboxilat", "oxicarbonil"),
+        (R::Amida, "amida", "carboxamida", "carbamoïl"),
+        (R::Nitril, "onitril", "carbonitril", "ciano"),
+        (R::Aldehid, "al", "carbaldehid", "formil"),
+        (R::Cetona, "ona", "ona", "oxo"),
+        (R::Alcohol, "ol", "ol", "hidroxi"),
+        (R::Amina, "amina", "amina", "amino"),
+    ],
+    halogens: [
+        (R::F, "fluoro"),
+        (R::Cl, "cloro"),
+        (R::Br, "bromo"),
+        (R::I, "iodo"),
+    ],
+    il: "il",
+    ilide: "iliden",
+    oxi: "oxi",
+    carbonil: "carbonil",
+    cicle: "ciclo",
+    fen: "fen",
+    eter: "èter",
+    terc: "terc-",
+    benzens: [
+        (None, "benzè"),
+        (Some(R::Carboxil), "benzoic"),
+        (Some(R::Ester), "benzoat"),
+        (Some(R::Amida), "benzamida"),
+        (Some(R::Nitril), "benzonitril"),
+        (Some(R::Aldehid), "benzaldehid"),
+        (Some(R::Alcohol), "fenol"),
+        (Some(R::Amina), "anilina"),
+    ],
+};
+
+pub(super) const CASTELLA: Lexic = Lexic {
+    idioma: Language::Spanish,
+    arrels: ["met", "et", "prop", "but"],
+    infixos: ["an", "en", "in"],
+    finals: ["ano", "eno", "ino"],
+    grups: [
+        (R::Carboxil, "oico", "carboxílico", "carboxi"),
+        (R::Ester, "oato", "carboxilato", "oxicarbonil"),
+        (R::Amida, "amida", "carboxamida", "carbamoil"),
+        (R::Nitril, "onitrilo", "carbonitrilo", "ciano"),
+        (R::Aldehid, "al", "carbaldehído", "formil"),
+        (R::Cetona, "ona", "ona", "oxo"),
+        (R::Alcohol, "ol", "ol", "hidroxi"),
+        (R::Amina, "amina", "amina", "amino"),
+    ],
+    halogens: [
+        (R::F, "fluoro"),
+        (R::Cl, "cloro"),
+        (R::Br, "bromo"),
+        (R::I, "yodo"),
+    ],
+    il: "il",
+    ilide: "ilideno",
+    oxi: "oxi",
+    carbonil: "carbonil",
+    cicle: "ciclo",
+    fen: "fen",
+    eter: "éter",
+    terc: "terc-",
+    benzens: [
+        (None, "benceno"),
+        (Some(R::Carboxil), "benzoico"),
+        (Some(R::Ester), "benzoato"),
+        (Some(R::Amida), "benzamida"),
+        (Some(R::Nitril), "benzonitrilo"),
+        (Some(R::Aldehid), "benzaldehído"),
+        (Some(R::Alcohol), "fenol"),
+        (Some(R::Amina), "anilina"),
+    ],
+};
+
+pub(super) const ANGLES: Lexic = Lexic {
+    idioma: Language::English,
+    arrels: ["meth", "eth", "prop", "but"],
+    infixos: ["an", "en", "yn"],
+    finals: ["ane", "ene", "yne"],
+    grups: [
+        (R::Carboxil, "oic", "carboxylic", "carboxy"),
+        (R::Ester, "oate", "carboxylate", "oxycarbonyl"),
+        (R::Amida, "amide", "carboxamide", "carbamoyl"),
+        (R::Nitril, "nitrile", "carbonitrile", "cyano"),
+        (R::Aldehid, "al", "carbaldehyde", "formyl"),
+        (R::Cetona, "one", "one", "oxo"),
+        (R::Alcohol, "ol", "ol", "hydroxy"),
+        (R::Amina, "amine", "amine", "amino"),
+    ],
+    halogens: [
+        (R::F, "fluoro"),
+        (R::Cl, "chloro"),
+        (R::Br, "bromo"),
+        (R::I, "iodo"),
+    ],
+    il: "yl",
+    ilide: "ylidene",
+    oxi: "oxy",
+    carbonil: "carbonyl",
+    cicle: "cyclo",
+    fen: "phen",
+    eter: "ether",
+    terc: "tert-",
+    benzens: [
+        (None, "benzene"),
+        (Some(R::Carboxil), "benzoic"),
+        (Some(R::Ester), "benzoate"),
+        (Some(R::Amida), "benzamide"),
+        (Some(R::Nitril), "benzonitrile"),
+        (Some(R::Aldehid), "benzaldehyde"),
+        (Some(R::Alcohol), "phenol"),
+        (Some(R::Amina), "aniline"),
+    ],
+};
+
+impl Lexic {
+    pub fn de(idioma: Language) -> &'static Self {
+        match idioma {
+            Language::Catalan => &CATALA,
+            Language::Spanish => &CASTELLA,
+            Language::English => &ANGLES,
+        }
+    }
+    fn grup(&self, r: R) -> Option<&(R, &'static str, &'static str, &'static str)> {
+        self.grups.iter().find(|(g, ..)| *g == r)
+    }
+    pub fn sufix(&self, principal: R) -> &'static str {
+        self.grup(principal)
+            .map(|(_, s, ..)| *s)
+            .expect("only called with groups from the table")
+    }
+    /// El sufix d'un grup terminal que penja d'un anell, on el seu carboni no pot formar part
+    /// de l'arrel: "ciclohexancarboxílic"
+    pub fn sufix_anell(&self, principal: R) -> &'static str {
+        self.grup(principal)
+            .map(|(_, _, s, _)| *s)
+            .expect("only called with groups from the table")
+    }
+    pub fn prefix(&self, r: R) -> Option<&'static str> {
+        self.halogens
+            .iter()
+            .find(|(h, _)| *h == r)
+            .map(|(_, p)| *p)
+            .or_else(|| self.grup(r).map(|(.., p)| *p))
+    }
+    /// El nom del benzè segons el grup principal que en penja, que sempre és a l'1, si el
+    /// derivat en té
+    pub fn benze(&self, principal: Option<R>) -> Option<&'static str> {
+        self.benzens
+            .iter()
+            .find(|(p, _)| *p == principal)
+            .map(|(_, nom)| *nom)
+    }
+    /// Afegeix el sufix al nom, amb els seus localitzadors ("-1,2-") davant. En castellà i en
+    /// anglès, la vocal de "-ano" i "-ane" es manté davant de consonant:
+    /// "ciclohexanocarboxílico", "propanenitrile", "etano-1,2-diol", però "propanol"
+    pub fn sufixa(&self, s: &mut String, localitzadors: &str, sufix: &str) {
+        if let Some(vocal) = self.vocal_d_enllac()
+            && !sufix.starts_with(['a', 'e', 'i', 'o', 'u', 'y'])
+            && !s.ends_with(vocal)
+        {
+            s.push_str(vocal);
+        }
+        s.push_str(localitzadors);
+        s.push_str(sufix);
+    }
+    pub fn vocal_d_enllac(&self) -> Option<&'static str> {
+        match self.idioma {
+            Language::Catalan => None,
+            Language::Spanish => Some("o"),
+            Language::English => Some("e"),
+        }
+    }
+    /// "àcid etanoic", "ácido etanoico", "ethanoic acid"
+    pub fn acid(&self, nom: &str) -> String {
+        match self.idioma {
+            Language::Catalan => format!("àcid {nom}"),
+            Language::Spanish => format!("ácido {nom}"),
+            Language::English => format!("{nom} acid"),
+        }
+    }
+    /// "etanoat de metil" / "d'etil", "etanoato de metilo", "methyl ethanoate"
+    pub fn ester(&self, nom: &str, alquil: &str) -> String {
+        match self.idioma {
+            Language::Catalan => match alquil.chars().next() {
+                Some('a' | 'e' | 'i' | 'o' | 'u' | 'h') => format!("{nom} d'{alquil}"),
+                _ => format!("{nom} de {alquil}"),
+            },
+            Language::Spanish => format!("{nom} de {alquil}o"),
+            Language::English => format!("{alquil} {nom}"),
+        }
+    }
+    /// El contrari d'`acid`, sobre el nom normalitzat (sense accents): "acid etanoic" -> "etanoic"
+    pub fn separa_acid<'a>(&self, nom: &'a str) -> Option<&'a str> {
+        match self.idioma {
+            Language::Catalan => nom.strip_prefix("acid "),
+            Language::Spanish => nom.strip_prefix("acido "),
+            Language::English => nom.strip_suffix(" acid"),
+        }
+    }
+    /// El contrari d'`ester`, sobre el nom normalitzat: "etanoat de metil" -> ("etanoat", "metil")
+    pub fn separa_ester<'a>(&self, nom: &'a str) -> Option<(&'a str, &'a str)> {
+        match self.idioma {
+            Language::Catalan => nom.split_once(" de ").or_else(|| nom.split_once(" d'")),
+            Language::Spanish => {
+                let (nom, alquil) = nom.split_once(" de ")?;
+                Some((nom, alquil.strip_suffix('o')?))
+            }
+            Language::English => nom.split_once(' ').map(|(alquil, nom)| (nom, alquil)),
+        }
+    }
+    /// Un pas de l'explicació: "s'escull la cadena de 6 carbonis davant la de 5 carbonis"
+    pub fn explica(&self, motiu: &Motiu) -> String {
+        use Motiu as M;
+        let ll = llista_localitzadors;
+        match self.idioma {
+            Language::Catalan => {
+                let de = |n: usize| match n {
+                    1 => "d'1 carboni".to_string(),
+                    11 => "d'11 carbonis".to_string(),
+                    _ => format!("de {n} carbonis"),
+                };
+                match motiu {
+                    M::GrupPrincipal(p) => {
+                        // "l'OH", però "el COOH": s'apostrofa davant les lletres que sonen amb vocal
+                        let article = match p.to_string().starts_with(['H', 'N', 'O']) {
+                            true => "l'",
+                            false => "el ",
+                        };
+                        format!("el grup principal és {article}{p} (-{})", self.sufix(*p))
+                    }
+                    M::SenseGrupPrincipal => "no hi ha grup principal".to_string(),
+                    M::UnaCadena => "només hi ha una cadena possible".to_string(),
+                    M::CadenesEquivalents => {
+                        "totes les cadenes possibles són equivalents".to_string()
+                    }
+                    M::MesPrincipals(a, b) => {
+                        format!("s'escull la cadena amb més grups principals ({a} davant {b})")
+                    }
+                    M::AnellOCadena(_, a, b) if a == b => {
+                        "a igualtat de carbonis, s'escull l'anell davant la cadena".to_string()
+                    }
+                    M::AnellOCadena(true, a, b) => {
+                        format!("s'escull l'anell {} davant la cadena {}", de(*a), de(*b))
+                    }
+                    M::AnellOCadena(false, a, b) => {
+                        format!("s'escull la cadena {} davant l'anell {}", de(*a), de(*b))
+                    }
+                    M::Anell => "s'escull l'anell davant la cadena".to_string(),
+                    M::MesInsaturada(a, b) => format!(
+                        "s'escull la cadena més insaturada ({a} {} davant {b})",
+                        match a {
+                            1 => "enllaç múltiple",
+                            _ => "enllaços múltiples",
+                        }
+                    ),
+                    M::MesLlarga(a, b) => {
+                        format!("s'escull la cadena {} davant la {}", de(*a), de(*b))
+                    }
+                    M::MesDobles(a, b) => {
+                        format!("s'escull la cadena amb més dobles enllaços ({a} davant {b})")
+                    }
+                    M::MesSubstituents(a, b) => {
+                        format!("s'escull la cadena amb més substituents ({a} davant {b})")
+                    }
+                    M::Localitzadors(quins, a, b) => format!(
+                        "localitzadors més baixos per {}: {} davant {}",
+                        match quins {
+                            Quins::Principals => "als grups principals",
+                            Quins::Multiples => "als enllaços múltiples",
+                            Quins::Dobles => "als dobles enllaços",
+                            Quins::Substituents => "als substituents",
+                        },
+                        ll(a),
+                        ll(b)
+                    ),
+                    M::OrdreAlfabetic(a, b) => format!(
+                        "a igualtat, el localitzador més baix és per al primer substituent en \
+                         ordre alfabètic ({} davant {})",
+                        ll(a),
+                        ll(b)
+                    ),
+                    M::Nom(nom, false) => format!("el nom és {nom}"),
+                    M::Nom(nom, true) => {
+                        format!("amb els substituents en ordre alfabètic davant, el nom és {nom}")
+                    }
+                }
+            }
+            Language::Spanish => {
+                let de = |n: usize| match n {
+                    1 => "de 1 carbono".to_string(),
+                    _ => format!("de {n} carbonos"),
+                };
+                match motiu {
+                    M::GrupPrincipal(p) => {
+                        format!("el grupo principal es el {p} (-{})", self.sufix(*p))
+                    }
+                    M::SenseGrupPrincipal => "no hay grupo principal".to_string(),
+                    M::UnaCadena => "solo hay una cadena posible".to_string(),
+                    M::CadenesEquivalents => {
+                        "todas las cadenas posibles son equivalentes".to_string()
+                    }
+                    M::MesPrincipals(a, b) => {
+                        format!("se escoge la cadena con más grupos principales ({a} frente a {b})")
+                    }
+                    M::AnellOCadena(_, a, b) if a == b => {
+                        "a igualdad de carbonos, se escoge el anillo frente a la cadena".to_string()
+                    }
+                    M::AnellOCadena(true, a, b) => {
+                        format!(
+                            "se escoge el anillo {} frente a la cadena {}",
+                            de(*a),
+                            de(*b)
+                        )
+                    }
+                    M::AnellOCadena(false, a, b) => {
+                        format!("se escoge la cadena {} frente al anillo {}", de(*a), de(*b))
+                    }
+                    M::Anell => "se escoge el anillo frente a la cadena".to_string(),
+                    M::MesInsaturada(a, b) => format!(
+                        "se escoge la cadena más insaturada ({a} {} frente a {b})",
+                        match a {
+                            1 => "enlace múltiple",
+                            _ => "enlaces múltiples",
+                        }
+                    ),
+                    M::MesLlarga(a, b) => {
+                        format!("se escoge la cadena {} frente a la {}", de(*a), de(*b))
+                    }
+                    M::MesDobles(a, b) => {
+                        format!("se escoge la cadena con más dobles enlaces ({a} frente a {b})")
+                    }
+                    M::MesSubstituents(a, b) => {
+                        format!("se escoge la cadena con más sustituyentes ({a} frente a {b})")
+                    }
+                    M::Localitzadors(quins, a, b) => format!(
+                        "localizadores más bajos para {}: {} frente a {}",
+                        match quins {
+                            Quins::Principals => "los grupos principales",
+                            Quins::Multiples => "los enlaces múltiples",
+                            Quins::Dobles => "los dobles enlaces",
+                            Quins::Substituents => "los sustituyentes",
+                        },
+                        ll(a),
+                        ll(b)
+                    ),
+                    M::OrdreAlfabetic(a, b) => format!(
+                        "a igualdad, el localizador más bajo es para el primer sustituyente en \
+                         orden alfabético ({} frente a {})",
+                        ll(a),
+                        ll(b)
+                    ),
+                    M::Nom(nom, false) => format!("el nombre es {nom}"),
+                    M::Nom(nom, true) => format!(
+                        "con los sustituyentes en orden alfabético delante, el nombre es {nom}"
+                    ),
+                }
+            }
+            Language::English => match motiu {
+                M::GrupPrincipal(p) => {
+                    format!("the principal group is {p} (-{})", self.sufix(*p))
+                }
+                M::SenseGrupPrincipal => "there is no principal group".to_string(),
+                M::UnaCadena => "there is only one possible chain".to_string(),
+                M::CadenesEquivalents => "all possible chains are equivalent".to_string(),
+                M::MesPrincipals(a, b) => {
+                    format!("chose the chain with more principal groups ({a} over {b})")
+                }
+                M::AnellOCadena(_, a, b) if a == b => {
+                    "with as many carbons, chose the ring over the chain".to_string()
+                }
+                M::AnellOCadena(true, a, b) => {
+                    format!("chose the {a}-carbon ring over the {b}-carbon chain")
+                }
+                M::AnellOCadena(false, a, b) => {
+                    format!("chose the {a}-carbon chain over the {b}-carbon ring")
+                }
+                M::Anell => "chose the ring over the chain".to_string(),
+                M::MesInsaturada(a, b) => format!(
+                    "chose the most unsaturated chain ({a} {} over {b})",
+                    match a {
+                        1 => "multiple bond",
+                        _ => "multiple bonds",
+                    }
+                ),
+                M::MesLlarga(a, b) => {
+                    format!("chose the {a}-carbon chain over the {b}-carbon one")
+                }
+                M::MesDobles(a, b) => {
+                    format!("chose the chain with more double bonds ({a} over {b})")
+                }
+                M::MesSubstituents(a, b) => {
+                    format!("chose the chain with more substituents ({a} over {b})")
+                }
+                M::Localitzadors(quins, a, b) => format!(
+                    "lowest locants for the {}: {} over {}",
+                    match quins {
+                        Quins::Principals => "principal groups",
+                        Quins::Multiples => "multiple bonds",
+                        Quins::Dobles => "double bonds",
+                        Quins::Substituents => "substituents",
+                    },
+                    ll(a),
+                    ll(b)
+                ),
+                M::OrdreAlfabetic(a, b) => format!(
+                    "on a tie, the lowest locant goes to the first substituent in alphabetical \
+                     order ({} over {})",
+                    ll(a),
+                    ll(b)
+                ),
+                M::Nom(nom, false) => format!("the name is {nom}"),
+                M::Nom(nom, true) => {
+                    format!(
+                        "with the substituents in alphabetical order in front, the name is {nom}"
+                    )
+                }
+            },
+        }
+    }
+}
@@ -0,0 +1,1315 @@
+//! De nom a estructura: el camí invers d'`anomena_molecula`, amb les mateixes taules de cada idioma
+
+use super::idiomes::Lexic;
+use super::{
+    EnllacDeBlocs, Graf, NOMS_CURTS, anomena_graf, arrel, es_terminal, multiplicador, noms_propis,
+    normalitza, numerals,
+};
+use crate::{
+    Edition, Molecule, NamingOptions, ParentChain, ParentKind, UiRadical, son_equivalents,
+};
+use UiRadical as R;
+
+/// Una molècula feta a partir d'un nom, llesta per posar-la al llenç
+#[derive(Debug, Clone, PartialEq)]
+pub struct Structure {
+    pub molecule: Molecule,
+    /// On va cada grup, com a (x, y) amb la y cap avall i en unitats de llargada d'enllaç
+    pub positions: Vec<(f32, f32)>,
+}
+
+/// Per què no s'ha pogut llegir un nom. `Display` en dona el missatge per a l'usuari
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// No hi ha cap nom
+    Empty,
+    /// El tros del nom, a partir d'on no l'entenem
+    Unknown(String),
+    /// Els localitzadors no quadren amb el multiplicador, com a "2-dimetil"
+    LocantCount(String),
+    /// Un localitzador que no pot anar on diu el nom: el 5 de "5-metilbutà" cau fora de la
+    /// cadena, i el 2 de "2-etilpentà" l'allargaria
+    InvalidLocant(usize),
+    /// Falten localitzadors i es poden posar de més d'una manera, com a "clorobutà", o n'hi ha
+    /// massa per provar-les totes
+    MissingLocants,
+    /// Un àtom amb més enllaços dels que pot fer
+    Valence(String),
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Escriu un nom"),
+            Self::Unknown(resta) => write!(f, "No entenc \"{resta}\""),
+            Self::LocantCount(que) => write!(f, "Els localitzadors de \"{que}\" no quadren"),
+            Self::InvalidLocant(l) => write!(f, "El localitzador {l} no hi pot anar"),
+            Self::MissingLocants => write!(f, "Falten localitzadors"),
+            Self::Valence(que) => write!(f, "Un {que} amb massa enllaços"),
+        }
+    }
+}
+
+/// Llegeix un nom sistemàtic o propi, en l'idioma de les opcions, i en fa la molècula
+pub fn parse_name(name: &str, opts: &NamingOptions) -> Result<Structure, ParseError> {
+    let nom = normalitza(name);
+    if nom.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    match noms_propis::patro(&nom, opts.language) {
+        Some((radicals, enllacos)) => Ok(propi(radicals, enllacos, opts)),
+        None => sistematic(&nom, opts),
+    }
+}
+
+/// Un nom propi es dibuixa com el seu sistemàtic, o en filera si encara no en sabem formar
+fn propi(radicals: &[R], enllacos: &[EnllacDeBlocs], opts: &NamingOptions) -> Structure {
+    anomena_graf(&Graf::de_blocs(radicals, enllacos), opts)
+        .ok()
+        .and_then(|s| sistematic(&normalitza(&s.name), opts).ok())
+        .unwrap_or_else(|| Structure {
+            molecule: Molecule::from_links(radicals, enllacos),
+            positions: (0..radicals.len()).map(|i| (i as f32, 0.0)).collect(),
+        })
+}
+
+fn sistematic(nom: &str, opts: &NamingOptions) -> Result<Structure, ParseError> {
+    let lx = Lexic::de(opts.language);
+    if let Some(alquils) = nom.strip_suffix(&format!(" {}", normalitza(lx.eter))) {
+        return eter(lx, alquils)?.estructura();
+    }
+
+    let nucli = lx.separa_acid(nom).unwrap_or(nom);
+    let (nucli, alquil_ester) = match lx.separa_ester(nucli) {
+        Some((nucli, alquil)) => (nucli, Some(alquil_d_ester(lx, alquil)?)),
+        None => (nucli, None),
+    };
+    let llegit = Lector { lx, s: nucli }.nom()?;
+    let llegit = Nom {
+        alquil_ester,
+        ..llegit
+    };
+    let es_ester = matches!(llegit.principal, Some(Principal { grup: R::Ester, .. }));
+    if es_ester != llegit.alquil_ester.is_some() {
+        return Err(ParseError::Unknown(nom.to_string()));
+    }
+    resol(&llegit, nom, opts)
+}
+
+/// "etil metil èter", "dietil èter"
+fn eter(lx: &'static Lexic, alquils: &str) -> Result<Molecula, ParseError> {
+    let (a, b) = match alquils.split_once(' ') {
+        Some((a, b)) => (alquil_sol(lx, a)?, alquil_sol(lx, b)?),
+        None => {
+            let a = alquils
+                .strip_prefix(&multiplicador(2))
+                .ok_or_else(|| ParseError::Unknown(alquils.to_string()))?;
+            let a = alquil_sol(lx, a)?;
+            (a, a)
+        }
+    };
+    let mut m = Molecula::default();
+    let o = m.afegeix(R::Eter, (0.0, 0.0));
+    for (alquil, d) in [(a, ESQUERRA), (b, DRETA)] {
+        let primer = m.alquil(alquil, d, d);
+        m.enllaca((o, None), primer, 1);
+    }
+    Ok(m)
+}
+
+/// L'alquil d'un èster, que amb més d'un grup èster du multiplicador: "butandioat de dimetil"
+fn alquil_d_ester(lx: &'static Lexic, nom: &str) -> Result<Alquil, ParseError> {
+    (2..=numerals::MAXIM)
+        .filter_map(|n| nom.strip_prefix(&multiplicador(n)))
+        .find_map(|alquil| alquil_sol(lx, alquil).ok())
+        .map_or_else(|| alquil_sol(lx, nom), Ok)
+}
+
+/// Un nom d'alquil i res més, com el de l'alcohol d'un èster
+fn alquil_sol(lx: &'static Lexic, nom: &str) -> Result<Alquil, ParseError> {
+    match (Lector { lx, s: nom }).alquil(0) {
+        Some((Substituent::Alquil(a), p)) if p == nom.len() => Ok(a),
+        _ => Err(ParseError::Unknown(nom.to_string())),
+    }
+}
+
+/// Localitzadors (si el nom en dona) i quantes vegades hi és la característica
+#[derive(Debug, Clone, PartialEq)]
+struct Localitzats {
+    locs: Option<Vec<usize>>,
+    n: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Alquil {
+    Cadena(usize),
+    Cicle(usize),
+    Fenil,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Substituent {
+    /// Un sol bloc, com "cloro" o "hidroxi". L'"oxo" és el mateix carboni fet cetona
+    Grup(R),
+    Alquil(Alquil),
+    /// =R, una cadena que penja amb un doble enllaç: "metiliden"
+    Ilide(usize),
+    /// -O-R
+    Alcoxi(Alquil),
+    /// -O-CO-R, amb els carbonis de l'acil (el del CO inclòs)
+    Aciloxi(usize),
+    /// -CO-O-R
+    Alcoxicarbonil(Alquil),
+    /// Un alquil amb els seus propis substituents, "(1-metilpropil)" o "isopropil": els
+    /// carbonis de la cadena i el que hi penja, amb tots els localitzadors
+    Ramificat(usize, Vec<(Localitzats, Substituent)>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Pare {
+    Cadena(usize),
+    Cicle(usize),
+    Benze,
+}
+
+impl Pare {
+    fn carbonis(&self) -> usize {
+        match self {
+            Self::Cadena(n) | Self::Cicle(n) => *n,
+            Self::Benze => 6,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Principal {
+    grup: R,
+    on: Localitzats,
+    /// Si el sufix és el d'anell ("carboxílic"), el carboni del grup no és de la cadena
+    penjat: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Nom {
+    prefixos: Vec<(Localitzats, Substituent)>,
+    pare: Pare,
+    dobles: Vec<Localitzats>,
+    triples: Vec<Localitzats>,
+    principal: Option<Principal>,
+    /// L'alquil d'un èster, el "metil" d'"etanoat de metil"
+    alquil_ester: Option<Alquil>,
+}
+
+impl Nom {
+    /// El grup principal que va a l'extrem de la cadena i per tant no du localitzador
+    fn principal_fix(&self) -> bool {
+        match &self.principal {
+            Some(p) => {
+                (self.pare == Pare::Benze && p.on.n == 1)
+                    || (es_terminal(p.grup) && !p.penjat && matches!(self.pare, Pare::Cadena(_)))
+            }
+            None => false,
+        }
+    }
+    /// Les característiques que poden dur localitzadors, amb el més gran que poden tenir
+    fn localitzables(&mut self) -> Vec<(&mut Localitzats, usize)> {
+        let n = self.pare.carbonis();
+        let enllacos = match self.pare {
+            Pare::Cadena(n) => n - 1,
+            Pare::Cicle(_) | Pare::Benze => n,
+        };
+        let fix = self.principal_fix();
+        let mut v: Vec<(&mut Localitzats, usize)> = vec![];
+        v.extend(self.prefixos.iter_mut().map(|(l, _)| (l, n)));
+        v.extend(self.dobles.iter_mut().map(|l| (l, enllacos)));
+        v.extend(self.triples.iter_mut().map(|l| (l, enllacos)));
+        if let Some(p) = &mut self.principal
+            && !fix
+        {
+            v.push((&mut p.on, n));
+        }
+        v
+    }
+}
+
+/// Els trossos que segueixen l'arrel
+#[derive(Default)]
+struct Cua {
+    dobles: Vec<Localitzats>,
+    triples: Vec<Localitzats>,
+    principal: Option<Principal>,
+}
+
+impl Cua {
+    fn insaturacio(&mut self, infix: usize, on: Localitzats) {
+        match infix {
+            1 => self.dobles.push(on),
+            _ => self.triples.push(on),
+        }
+    }
+}
+
+/// Llegeix un nom ja normalitzat. Cada mètode mira de llegir una cosa a partir d'una posició i,
+/// si hi és, torna la posició on acaba
+struct Lector<'a> {
+    lx: &'static Lexic,
+    s: &'a str,
+}
+
+impl Lector<'_> {
+    fn nom(&self) -> Result<Nom, ParseError> {
+        // "o-diclorobenzè"
+        let (omp, inici) = match ["o-", "m-", "p-"]
+            .iter()
+            .position(|p| self.s.starts_with(p))
+        {
+            Some(k) => (Some(k + 2), 2),
+            None => (None, 0),
+        };
+        let (prefixos, pos) = self.prefixos(inici)?;
+        let Some((pare, cua)) = self.pare(pos) else {
+            return Err(ParseError::Unknown(self.s[pos..].to_string()));
+        };
+        let mut nom = Nom {
+            prefixos,
+            pare,
+            dobles: cua.dobles,
+            triples: cua.triples,
+            principal: cua.principal,
+            alquil_ester: None,
+        };
+
+        if let Some(l) = omp {
+            // El grup principal és a l'1, si n'hi ha; si no, el primer substituent
+            let mut locs = match nom.principal {
+                Some(_) => vec![l],
+                None => vec![1, l],
+            };
+            let mut quadren = nom.pare == Pare::Benze;
+            for (on, _) in &mut nom.prefixos {
+                let resta = locs.split_off(on.n.min(locs.len()));
+                let seus = std::mem::replace(&mut locs, resta);
+                quadren &= seus.len() == on.n;
+                on.locs = Some(seus);
+            }
+            if !quadren || !locs.is_empty() {
+                return Err(ParseError::LocantCount(self.s.to_string()));
+            }
+        }
+        Ok(nom)
+    }
+
+    fn paraula(&self, pos: usize, p: &str) -> Option<usize> {
+        let p = normalitza(p);
+        self.s[pos..].starts_with(&p).then_some(pos + p.len())
+    }
+
+    /// "2,3"
+    fn numeros(&self, pos: usize) -> Option<(Vec<usize>, usize)> {
+        let mut locs = vec![];
+        let mut p = pos;
+        loop {
+            let xifres = self.s[p..].bytes().take_while(u8::is_ascii_digit).count();
+            if xifres == 0 {
+                return None;
+            }
+            locs.push(self.s[p..p + xifres].parse().ok()?);
+            p += xifres;
+            match self.paraula(p, ",") {
+                Some(seguent) => p = seguent,
+                None => return Some((locs, p)),
+            }
+        }
+    }
+
+    /// "-2,3-", els localitzadors d'una insaturació o del sufix
+    fn entre_guions(&self, pos: usize) -> Option<(Vec<usize>, usize)> {
+        let (locs, p) = self.numeros(self.paraula(pos, "-")?)?;
+        Some((locs, self.paraula(p, "-")?))
+    }
+
+    /// Els multiplicadors que poden començar a `pos`, del més llarg al més curt, i al final
+    /// el de no haver-n'hi cap
+    fn multiplicadors(&self, pos: usize) -> Vec<(usize, usize)> {
+        let mut v: Vec<(usize, usize)> = (2..=numerals::MAXIM)
+            .flat_map(|n| [multiplicador(n), numerals::multiplicador(n, true)].map(|m| (n, m)))
+            .filter_map(|(n, m)| Some((n, self.paraula(pos, &m)?)))
+            .collect();
+        v.sort_by_key(|(_, p)| std::cmp::Reverse(*p));
+        v.push((1, pos));
+        v
+    }
+
+    fn arrel(&self, pos: usize) -> Option<(usize, usize)> {
+        (1..=numerals::MAXIM)
+            .filter_map(|n| Some((n, self.paraula(pos, &arrel(self.lx, n).ok()?)?)))
+            .max_by_key(|(_, p)| *p)
+    }
+
+    /// Els substituents, amb els seus localitzadors i multiplicadors: "2-cloro-3,3-dimetil"
+    fn prefixos(
+        &self,
+        mut pos: usize,
+    ) -> Result<(Vec<(Localitzats, Substituent)>, usize), ParseError> {
+        let mut prefixos = vec![];
+        loop {
+            let (locs, inici) = match self.numeros(pos) {
+                Some((locs, p)) if let Some(p) = self.paraula(p, "-") => (Some(locs), p),
+                _ => (None, pos),
+            };
+            let mut candidats: Vec<_> = self
+                .multiplicadors(inici)
+                .into_iter()
+                // "di-terc-butil"
+                .flat_map(|(n, p)| {
+                    [Some(p), self.paraula(p, "-").filter(|_| n > 1)].map(|p| (n, p))
+                })
+                .filter_map(|(n, p)| self.substituent(p?).map(|(sub, p)| (n, sub, p)))
+                .collect();
+            // Primer el que quadra amb els localitzadors: "5-tridecil" és un tridecil, no tres
+            // decils
+            let quants = locs.as_ref().map_or(1, Vec::len);
+            candidats.sort_by_key(|(n, _, _)| *n != quants);
+            let Some((n, sub, p)) = candidats.into_iter().next() else {
+                break;
+            };
+            if locs.as_ref().is_some_and(|ls| ls.len() != n) {
+                return Err(ParseError::LocantCount(self.s[pos..p].to_string()));
+            }
+            prefixos.push((Localitzats { locs, n }, sub));
+            pos = p;
+            // "2-cloro-3-metil"
+            if let Some(p) = self.paraula(pos, "-")
+                && self.numeros(p).is_some()
+            {
+                pos = p;
+            }
+        }
+        Ok((prefixos, pos))
+    }
+
+    fn substituent(&self, pos: usize) -> Option<(Substituent, usize)> {
+        if let Some(p) = self.paraula(pos, "(") {
+            return self.complex(p);
+        }
+        let grup = self
+            .lx
+            .halogens
+            .iter()
+            .map(|(r, p)| (*r, *p))
+            .chain(self.lx.grups.iter().map(|(r, .., p)| (*r, *p)))
+            .filter(|(r, _)| *r != R::Ester)
+            .filter_map(|(r, prefix)| Some((r, self.paraula(pos, prefix)?)))
+            .max_by_key(|(_, p)| *p);
+        match grup {
+            Some((r, p)) => Some((Substituent::Grup(r), p)),
+            None => self.alquil(pos),
+        }
+    }
+
+    /// "(metoxicarbonil)", "(etanoiloxi)", "(1-metiletil)"
+    fn complex(&self, pos: usize) -> Option<(Substituent, usize)> {
+        if let Some(ramificat) = self.ramificat(pos) {
+            return Some(ramificat);
+        }
+        let (sub, p) = match self.alquil(pos) {
+            Some((Substituent::Alcoxi(a), p))
+                if let Some(p) = self.paraula(p, self.lx.carbonil) =>
+            {
+                (Substituent::Alcoxicarbonil(a), p)
+            }
+            _ => {
+                let (n, p) = self.arrel(pos)?;
+                let p = self.paraula(p, "ano")?;
+                let p = self.paraula(p, self.lx.il)?;
+                (Substituent::Aciloxi(n), self.paraula(p, self.lx.oxi)?)
+            }
+        };
+        Some((sub, self.paraula(p, ")")?))
+    }
+
+    /// "1-metiletil)": els substituents amb els seus localitzadors, que només es poden ometre
+    /// en un metil, i l'alquil de la cadena al final
+    fn ramificat(&self, pos: usize) -> Option<(Substituent, usize)> {
+        let (mut prefixos, p) = self.prefixos(pos).ok()?;
+        let (Localitzats { locs: None, n: 1 }, Substituent::Alquil(Alquil::Cadena(n))) =
+            prefixos.pop()?
+        else {
+            return None;
+        };
+        if prefixos.is_empty() {
+            return None;
+        }
+        for (on, _) in &mut prefixos {
+            match on.locs {
+                Some(_) => {}
+                None if n == 1 => on.locs = Some(vec![1; on.n]),
+                None => return None,
+            }
+        }
+        Some((Substituent::Ramificat(n, prefixos), self.paraula(p, ")")?))
+    }
+
+    /// "isopropil", "terc-butil"
+    fn nom_curt(&self, pos: usize) -> Option<(Substituent, usize)> {
+        NOMS_CURTS
+            .iter()
+            .find_map(|&(prefix, total, cadena, metils)| {
+                let prefix = if prefix == "terc-" {
+                    self.lx.terc
+                } else {
+                    prefix
+                };
+                let p = self.paraula(pos, prefix)?;
+                let p = self.paraula(p, &arrel(self.lx, total).ok()?)?;
+                let p = self.paraula(p, self.lx.il)?;
+                let metils = Localitzats {
+                    locs: Some(metils.to_vec()),
+                    n: metils.len(),
+                };
+                let metil = Substituent::Alquil(Alquil::Cadena(1));
+                Some((Substituent::Ramificat(cadena, vec![(metils, metil)]), p))
+            })
+    }
+
+    /// "metil", "ciclohexil", "fenil", i també "metoxi", "pentiloxi", "fenoxi"
+    fn alquil(&self, pos: usize) -> Option<(Substituent, usize)> {
+        if let Some(curt) = self.nom_curt(pos) {
+            return Some(curt);
+        }
+        let (a, p) = if let Some(p) = self.paraula(pos, self.lx.fen) {
+            (Alquil::Fenil, p)
+        } else if let Some(p) = self.paraula(pos, self.lx.cicle)
+            && let Some((n, p)) = self.arrel(p)
+        {
+            (Alquil::Cicle(n), p)
+        } else {
+            let (n, p) = self.arrel(pos)?;
+            (Alquil::Cadena(n), p)
+        };
+        if let Alquil::Cadena(n) = a
+            && let Some(p) = self.paraula(p, self.lx.ilide)
+        {
+            return Some((Substituent::Ilide(n), p));
+        }
+        let il = |p| self.paraula(p, self.lx.il);
+        let oxi = |p| self.paraula(p, self.lx.oxi);
+        if let Some(p) = il(p).and_then(oxi).or_else(|| oxi(p)) {
+            return Some((Substituent::Alcoxi(a), p));
+        }
+        Some((Substituent::Alquil(a), il(p)?))
+    }
+
+    /// La cadena o l'anell principal, amb tot el que el segueix fins al final
+    fn pare(&self, pos: usize) -> Option<(Pare, Cua)> {
+        for (grup, nom) in self.lx.benzens {
+            if self.paraula(pos, nom) == Some(self.s.len()) {
+                let principal = grup.map(|grup| Principal {
+                    grup,
+                    on: Localitzats { locs: None, n: 1 },
+                    penjat: true,
+                });
+                return Some((
+                    Pare::Benze,
+                    Cua {
+                        principal,
+                        ..Default::default()
+                    },
+                ));
+            }
+        }
+        // "benzè-1,2-diol"
+        if let Some(p) = self.paraula(pos, self.lx.benze(None)?)
+            && let Some(cua) = self.cua(p, true)
+            && cua.principal.is_some()
+        {
+            return Some((Pare::Benze, cua));
+        }
+        // Els localitzadors davant de l'arrel, com al 1979: "2-butanol", "1,3-butadiè"
+        let (davant, pos) = match self.numeros(pos) {
+            Some((locs, p)) if let Some(p) = self.paraula(p, "-") => (Some(locs), p),
+            _ => (None, pos),
+        };
+        let (cicle, p) = match self.paraula(pos, self.lx.cicle) {
+            Some(p) => (true, p),
+            None => (false, pos),
+        };
+        let (n, p) = self.arrel(p)?;
+        // La "a" eufònica de "buta-1,3-diè" o de "butadiè"
+        let eufonica = self.paraula(p, "a");
+        let mut cua = [Some(p), eufonica]
+            .into_iter()
+            .flatten()
+            .find_map(|p| self.cua(p, false))?;
+        if let Some(locs) = davant {
+            // Són de la primera cosa que no en du
+            let on = cua
+                .dobles
+                .iter_mut()
+                .chain(&mut cua.triples)
+                .chain(cua.principal.as_mut().map(|p| &mut p.on))
+                .find(|on| on.locs.is_none())?;
+            if on.n != locs.len() {
+                return None;
+            }
+            on.locs = Some(locs);
+        }
+        Some((
+            if cicle {
+                Pare::Cicle(n)
+            } else {
+                Pare::Cadena(n)
+            },
+            cua,
+        ))
+    }
+
+    /// Les insaturacions i el sufix, fins al final. `infix` diu si ja n'hi ha hagut cap ("an",
+    /// "en", "in")
+    fn cua(&self, pos: usize, infix: bool) -> Option<Cua> {
+        let (locs, p) = match self.entre_guions(pos) {
+            Some((locs, p)) => (Some(locs), p),
+            None => (None, pos),
+        };
+        for (n, p) in self.multiplicadors(p) {
+            if locs.as_ref().is_some_and(|ls| ls.len() != n) {
+                continue;
+            }
+            let on = Localitzats {
+                locs: locs.clone(),
+                n,
+            };
+            let resta = &self.s[p..];
+            let sense_res = on.locs.is_none() && n == 1;
+
+            // La terminació sense sufix: "à", "è", "í"
+            for (k, fi) in self.lx.finals.iter().enumerate() {
+                if resta == normalitza(fi) && (k > 0 || (!infix && sense_res)) {
+                    let mut cua = Cua::default();
+                    if k > 0 {
+                        cua.insaturacio(k, on);
+                    }
+                    return Some(cua);
+                }
+            }
+            if infix {
+                for (grup, sufix, sufix_anell, _) in self.lx.grups {
+                    for (paraula, penjat) in [(sufix, false), (sufix_anell, sufix_anell != sufix)] {
+                        if resta == normalitza(paraula) {
+                            return Some(Cua {
+                                principal: Some(Principal { grup, on, penjat }),
+                                ..Default::default()
+                            });
+                        }
+                    }
+                }
+            }
+            for (k, infix_k) in self.lx.infixos.iter().enumerate() {
+                if k == 0 && (infix || !sense_res) {
+                    continue;
+                }
+                let Some(seguent) = self.paraula(p, infix_k) else {
+                    continue;
+                };
+                // "propanenitrile", "ciclohexanocarboxílico"
+                let enllac = self
+                    .lx
+                    .vocal_d_enllac()
+                    .and_then(|v| self.paraula(seguent, v));
+                for seguent in [Some(seguent), enllac].into_iter().flatten() {
+                    if let Some(mut cua) = self.cua(seguent, true) {
+                        if k > 0 {
+                            cua.insaturacio(k, on.clone());
+                        }
+                        return Some(cua);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Posa els localitzadors que falten provant-los tots, fins a trobar la molècula que passa
+/// `comprova`: "metilbutà" és el 2-metilbutà, perquè l'1- i el 4- allargarien la cadena. Si
+/// n'hi ha més d'una, com a "clorobutà", falten localitzadors
+fn resol(nom: &Nom, text: &str, opts: &NamingOptions) -> Result<Structure, ParseError> {
+    const MAXIM_DE_PROVES: usize = 200;
+
+    let mut plantilla = nom.clone();
+    let buits: Vec<(usize, usize)> = plantilla
+        .localitzables()
+        .into_iter()
+        .filter(|(l, _)| l.locs.is_none())
+        .map(|(l, maxim)| (l.n, maxim))
+        .collect();
+    if buits.is_empty() {
+        return comprova(nom, construeix(nom)?.estructura()?, text, opts);
+    }
+    // Abans de fer-les, que "pentametilpentadecà" ja en dona més de 10.000
+    if buits
+        .iter()
+        .try_fold(1usize, |total, &(n, maxim)| {
+            total.checked_mul(quantes_combinacions(n, maxim)?)
+        })
+        .is_none_or(|total| total > MAXIM_DE_PROVES)
+    {
+        return Err(ParseError::MissingLocants);
+    }
+    let opcions: Vec<Vec<Vec<usize>>> = buits
+        .into_iter()
+        .map(|(n, maxim)| combinacions(n, maxim))
+        .collect();
+
+    let mut tria = vec![0; opcions.len()];
+    let mut trobada: Option<Structure> = None;
+    let mut error = None;
+    loop {
+        let mut prova = nom.clone();
+        let buits = prova
+            .localitzables()
+            .into_iter()
+            .filter(|(l, _)| l.locs.is_none());
+        for (k, (l, _)) in buits.enumerate() {
+            l.locs = Some(opcions[k][tria[k]].clone());
+        }
+        match construeix(&prova).and_then(Molecula::estructura) {
+            Ok(s) => match (&trobada, comprova(&prova, s, text, opts)) {
+                (_, Err(_)) => {}
+                (None, Ok(s)) => trobada = Some(s),
+                // La mateixa molècula, amb els localitzadors comptats de l'altra punta
+                (Some(primera), Ok(s)) if son_equivalents(&primera.molecule, &s.molecule) => {}
+                (Some(_), Ok(_)) => return Err(ParseError::MissingLocants),
+            },
+            Err(e) => error = Some(e),
+        }
+
+        // La següent combinació, com un comptador
+        let mut k = opcions.len();
+        loop {
+            if k == 0 {
+                return match (trobada, error) {
+                    (Some(s), _) => Ok(s),
+                    // Cap no es diu així, com "ciclopropilmetà", que és el metilciclopropà
+                    (None, None) => Err(ParseError::Unknown(text.to_string())),
+                    (None, Some(e)) => Err(e),
+                };
+            }
+            k -= 1;
+            tria[k] += 1;
+            if tria[k] < opcions[k].len() {
+                break;
+            }
+            tria[k] = 0;
+        }
+    }
+}
+
+/// Comprova que la cadena del nom, que ja té tots els localitzadors, sigui la principal de la
+/// seva molècula en alguna edició. Localitzadors de més, una numeració que no és la més baixa
+/// o prefixos fora d'ordre alfabètic no la canvien, però un alquil que allarga la cadena sí: el
+/// 2 de "2-etilpentà", que és el 3-metilhexà, no hi pot anar. Un anell sempre pot ser el pare,
+/// com a l'edició del 2013
+fn comprova(
+    nom: &Nom,
+    s: Structure,
+    text: &str,
+    opts: &NamingOptions,
+) -> Result<Structure, ParseError> {
+    let Pare::Cadena(n) = nom.pare else {
+        return Ok(s);
+    };
+    let g = Graf::new(&s.molecule);
+    let pares: Vec<ParentChain> = [Edition::Iupac1979, Edition::Iupac1993, Edition::Iupac2013]
+        .into_iter()
+        .filter_map(|edition| anomena_graf(&g, &NamingOptions { edition, ..*opts }).ok())
+        .map(|nom| nom.parent)
+        .collect();
+    // Si no la sabem anomenar, no la podem comprovar
+    if pares.is_empty()
+        || pares
+            .iter()
+            .any(|p| p.kind == ParentKind::Chain && p.carbons == n)
+    {
+        return Ok(s);
+    }
+    for (on, sub) in &nom.prefixos {
+        let llargada = match sub {
+            Substituent::Alquil(Alquil::Cadena(k))
+            | Substituent::Ilide(k)
+            | Substituent::Ramificat(k, _) => *k,
+            _ => continue,
+        };
+        // Més llarg que la cadena cap a un dels dos costats
+        let allarga = |l: &&usize| llargada > (**l - 1).min(n - **l);
+        if let Some(&l) = on.locs.iter().flatten().find(allarga) {
+            return Err(ParseError::InvalidLocant(l));
+        }
+    }
+    Err(ParseError::Unknown(text.to_string()))
+}
+
+/// Totes les llistes creixents (amb repeticions) de `n` localitzadors entre 1 i `maxim`
+fn combinacions(n: usize, maxim: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![vec![]];
+    }
+    let mut v = vec![];
+    for primer in 1..=maxim {
+        for mut resta in combinacions(n - 1, maxim) {
+            if resta.first().is_none_or(|l| *l >= primer) {
+                resta.insert(0, primer);
+                v.push(resta);
+            }
+        }
+    }
+    v
+}
+
+/// Quantes llistes dona `combinacions(n, maxim)`, sense fer-les
+fn quantes_combinacions(n: usize, maxim: usize) -> Option<usize> {
+    (0..n).try_fold(1usize, |total, i| {
+        Some(total.checked_mul(maxim + i)? / (i + 1))
+    })
+}
+
+/// Munta la molècula d'un nom que ja té tots els localitzadors
+fn construeix(nom: &Nom) -> Result<Molecula, ParseError> {
+    let n = nom.pare.carbonis();
+    let lloc = |l: usize| {
+        (1..=n)
+            .contains(&l)
+            .then(|| l - 1)
+            .ok_or(ParseError::InvalidLocant(l))
+    };
+    // Una cetona a l'extrem de la cadena seria un aldehid
+    let cetona_a_l_extrem = |grup: &Substituent, i: usize| {
+        *grup == Substituent::Grup(R::Cetona)
+            && matches!(nom.pare, Pare::Cadena(_))
+            && (i == 0 || i == n - 1)
+    };
+    let mut m = Molecula::default();
+
+    // Els grups terminals i les cetones substitueixen carbonis de la cadena
+    let mut radicals = vec![R::C; n];
+    let mut penjats = vec![];
+    if let Some(p) = &nom.principal {
+        let locs = match &p.on.locs {
+            Some(locs) => locs.clone(),
+            None if p.on.n == 2 => vec![1, n],
+            None => vec![1],
+        };
+        for l in locs {
+            let i = lloc(l)?;
+            if cetona_a_l_extrem(&Substituent::Grup(p.grup), i) {
+                return Err(ParseError::InvalidLocant(l));
+            }
+            match nom.pare {
+                Pare::Cadena(_) if es_terminal(p.grup) && !p.penjat => {
+                    if i != 0 && i != n - 1 {
+                        return Err(ParseError::InvalidLocant(l));
+                    }
+                    radicals[i] = p.grup;
+                }
+                Pare::Cadena(_) | Pare::Cicle(_) if p.grup == R::Cetona => radicals[i] = R::Cetona,
+                _ => penjats.push((i, p.grup)),
+            }
+        }
+    }
+
+    let mut llocs = m.esquelet(nom.pare, &radicals);
+    let mut ordres = vec![1; n];
+    for (ordre, on) in (nom.dobles.iter().map(|l| (2, l))).chain(nom.triples.iter().map(|l| (3, l)))
+    {
+        for &l in on.locs.iter().flatten() {
+            let maxim = if let Pare::Cadena(_) = nom.pare {
+                n - 1
+            } else {
+                n
+            };
+            if l > maxim || nom.pare == Pare::Benze {
+                return Err(ParseError::InvalidLocant(l));
+            }
+            ordres[lloc(l)?] = ordre;
+        }
+    }
+    m.enllaca_esquelet(nom.pare, &ordres);
+
+    for (i, grup) in penjats {
+        let sub = match (grup, nom.alquil_ester) {
+            (R::Ester, Some(alquil)) => Substituent::Alcoxicarbonil(alquil),
+            (grup, _) => Substituent::Grup(grup),
+        };
+        m.penja(&mut llocs[i], sub)?;
+    }
+    // L'alcohol dels èsters de cadena
+    if let Some(alquil) = nom.alquil_ester {
+        for i in (0..n).filter(|&i| radicals[i] == R::Ester) {
+            m.penja(&mut llocs[i], Substituent::Alquil(alquil))?;
+        }
+    }
+    for (on, sub) in &nom.prefixos {
+        for &l in on.locs.iter().flatten() {
+            let i = lloc(l)?;
+            if cetona_a_l_extrem(sub, i) {
+                return Err(ParseError::InvalidLocant(l));
+            }
+            m.penja(&mut llocs[i], sub.clone())?;
+        }
+    }
+    Ok(m)
+}
+
+const AMUNT: (f32, f32) = (0.0, -1.0);
+const ESQUERRA: (f32, f32) = (-1.0, 0.0);
+const AVALL: (f32, f32) = (0.0, 1.0);
+const DRETA: (f32, f32) = (1.0, 0.0);
+
+fn suma((x, y): (f32, f32), (dx, dy): (f32, f32), k: f32) -> (f32, f32) {
+    (x + k * dx, y + k * dy)
+}
+
+fn angle(graus: f32) -> (f32, f32) {
+    let a = graus.to_radians();
+    (a.cos(), a.sin())
+}
+
+/// Cap on surt cada port, en el mateix ordre que `UiBlock::port_positions`
+fn direccions_dels_ports(r: R) -> Vec<(f32, f32)> {
+    match r.valencia() {
+        1 => vec![DRETA],
+        2 => vec![ESQUERRA, DRETA],
+        3 => vec![AMUNT, ESQUERRA, DRETA],
+        6 => (0..6).map(|k| angle(-90.0 + 60.0 * k as f32)).collect(),
+        _ => vec![AMUNT, ESQUERRA, AVALL, DRETA],
+    }
+}
+
+/// Un carboni de la cadena principal on es pot penjar alguna cosa
+struct Lloc {
+    node: usize,
+    /// El port per on penja, si el bloc en té de diferents (èster, benzè)
+    port: Option<usize>,
+    pos: (f32, f32),
+    /// Cap on pot anar el següent substituent, el primer al final
+    direccions: Vec<(f32, f32)>,
+}
+
+/// Un extrem d'un enllaç: el bloc i, si ja s'ha triat, el port
+pub(crate) type Extrem = (usize, Option<usize>);
+
+/// La molècula a mig fer: els enllaços encara no tenen tots els ports triats
+#[derive(Default)]
+pub(crate) struct Molecula {
+    radicals: Vec<R>,
+    posicions: Vec<(f32, f32)>,
+    enllacos: Vec<(Extrem, Extrem)>,
+}
+
+impl Molecula {
+    pub(crate) fn afegeix(&mut self, r: R, pos: (f32, f32)) -> usize {
+        self.radicals.push(r);
+        self.posicions.push(pos);
+        self.radicals.len() - 1
+    }
+
+    pub(crate) fn enllaca(&mut self, a: Extrem, b: Extrem, ordre: usize) {
+        for _ in 0..ordre {
+            self.enllacos.push((a, b));
+        }
+    }
+
+    /// Els carbonis de la cadena o l'anell principal, encara sense enllaçar
+    fn esquelet(&mut self, pare: Pare, radicals: &[R]) -> Vec<Lloc> {
+        let n = radicals.len();
+        match pare {
+            Pare::Benze => {
+                let b = self.afegeix(R::Benze, (0.0, 0.0));
+                (0..6)
+                    .map(|k| Lloc {
+                        node: b,
+                        port: Some(k),
+                        pos: (0.0, 0.0),
+                        direccions: vec![angle(-90.0 + 60.0 * k as f32)],
+                    })
+                    .collect()
+            }
+            Pare::Cadena(_) => (0..n)
+                .map(|i| {
+                    let pos = (i as f32, 0.0);
+                    let direccions = match (i, n) {
+                        (_, 1) => vec![ESQUERRA, AVALL, AMUNT, DRETA],
+                        (0, _) => vec![AVALL, AMUNT, ESQUERRA],
+                        (i, n) if i == n - 1 => vec![AVALL, AMUNT, DRETA],
+                        _ => vec![AVALL, AMUNT],
+                    };
+                    Lloc {
+                        node: self.afegeix(radicals[i], pos),
+                        // Un èster s'enllaça a la cadena pel carboni i a l'alquil per l'oxigen
+                        port: (radicals[i] == R::Ester).then_some(1),
+                        pos,
+                        direccions,
+                    }
+                })
+                .collect(),
+            Pare::Cicle(_) => {
+                let radi = radi_d_anell(n);
+                (0..n)
+                    .map(|i| {
+                        let graus = -90.0 + 360.0 * i as f32 / n as f32;
+                        let pos = suma((0.0, 0.0), angle(graus), radi);
+                        Lloc {
+                            node: self.afegeix(radicals[i], pos),
+                            port: None,
+                            pos,
+                            direccions: vec![
+                                angle(graus - 35.0),
+                                angle(graus + 35.0),
+                                angle(graus),
+                            ],
+                        }
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Enllaça els carbonis d'`esquelet`. `ordres[i]` és l'ordre de l'enllaç que surt de l'i
+    fn enllaca_esquelet(&mut self, pare: Pare, ordres: &[usize]) {
+        let n = ordres.len();
+        let port = |m: &Self, i: usize| (m.radicals[i] == R::Ester).then_some(0);
+        let enllacos = match pare {
+            Pare::Benze => 0,
+            Pare::Cadena(_) => n - 1,
+            Pare::Cicle(_) => n,
+        };
+        for (i, ordre) in ordres.iter().enumerate().take(enllacos) {
+            let j = (i + 1) % n;
+            let (a, b) = ((i, port(self, i)), (j, port(self, j)));
+            self.enllaca(a, b, *ordre);
+        }
+    }
+
+    /// Penja el substituent del lloc, cap a la primera direcció que li quedi
+    fn penja(&mut self, lloc: &mut Lloc, sub: Substituent) -> Result<(), ParseError> {
+        let node = lloc.node;
+        let massa = |m: &Self| ParseError::Valence(m.radicals[node].to_string());
+        if sub == Substituent::Grup(R::Cetona) {
+            // L'"oxo" no penja: el carboni mateix passa a ser la cetona
+            if self.radicals[node] != R::C {
+                return Err(massa(self));
+            }
+            self.radicals[node] = R::Cetona;
+            return Ok(());
+        }
+        let d = lloc.direccions.pop().ok_or_else(|| massa(self))?;
+        let p = suma(lloc.pos, d, 1.0);
+        let de = (lloc.node, lloc.port);
+        match sub {
+            Substituent::Grup(r) => {
+                let g = self.afegeix(r, p);
+                self.enllaca(de, (g, None), 1);
+            }
+            Substituent::Alquil(a) => {
+                let primer = self.alquil(a, p, d);
+                self.enllaca(de, primer, 1);
+            }
+            Substituent::Ilide(n) => {
+                let primer = self.alquil(Alquil::Cadena(n), p, d);
+                self.enllaca(de, primer, 2);
+            }
+            Substituent::Alcoxi(a) => {
+                let o = self.afegeix(R::Eter, p);
+                self.enllaca(de, (o, None), 1);
+                let primer = self.alquil(a, suma(p, d, 1.0), d);
+                self.enllaca((o, None), primer, 1);
+            }
+            Substituent::Aciloxi(n) => {
+                let e = self.afegeix(R::Ester, p);
+                self.enllaca(de, (e, Some(1)), 1);
+                if n > 1 {
+                    let primer = self.alquil(Alquil::Cadena(n - 1), suma(p, d, 1.0), d);
+                    self.enllaca((e, Some(0)), primer, 1);
+                }
+            }
+            Substituent::Alcoxicarbonil(a) => {
+                let e = self.afegeix(R::Ester, p);
+                self.enllaca(de, (e, Some(0)), 1);
+                let primer = self.alquil(a, suma(p, d, 1.0), d);
+                self.enllaca((e, Some(1)), primer, 1);
+            }
+            Substituent::Ramificat(n, prefixos) => {
+                let (primer, _) = self.alquil(Alquil::Cadena(n), p, d);
+                self.enllaca(de, (primer, None), 1);
+                // Els seus substituents surten pels costats, i pel final l'últim
+                let costats = [(-d.1, d.0), (d.1, -d.0)];
+                let mut llocs: Vec<Lloc> = (0..n)
+                    .map(|k| Lloc {
+                        node: primer + k,
+                        port: None,
+                        pos: suma(p, d, k as f32),
+                        direccions: match k == n - 1 {
+                            true => vec![costats[0], costats[1], d],
+                            false => costats.to_vec(),
+                        },
+                    })
+                    .collect();
+                for (on, sub) in prefixos {
+                    for &l in on.locs.iter().flatten() {
+                        let lloc = l
+                            .checked_sub(1)
+                            .and_then(|i| llocs.get_mut(i))
+                            .ok_or(ParseError::InvalidLocant(l))?;
+                        self.penja(lloc, sub.clone())?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Posa un alquil que comença a `pos` i creix cap a `d`. Torna el bloc (i el port) per on
+    /// s'enganxa
+    fn alquil(&mut self, a: Alquil, pos: (f32, f32), d: (f32, f32)) -> Extrem {
+        match a {
+            Alquil::Cadena(n) => {
+                let primer = self.afegeix(R::C, pos);
+                for k in 1..n {
+                    let c = self.afegeix(R::C, suma(pos, d, k as f32));
+                    self.enllaca((c - 1, None), (c, None), 1);
+                }
+                (primer, None)
+            }
+            Alquil::Cicle(n) => {
+                let radi = radi_d_anell(n);
+                let centre = suma(pos, d, radi);
+                let inici = (-d.1).atan2(-d.0).to_degrees();
+                let primer = self.radicals.len();
+                for k in 0..n {
+                    let graus = inici + 360.0 * k as f32 / n as f32;
+                    self.afegeix(R::C, suma(centre, angle(graus), radi));
+                }
+                for k in 0..n {
+                    let (a, b) = (primer + k, primer + (k + 1) % n);
+                    self.enllaca((a, None), (b, None), 1);
+                }
+                (primer, None)
+            }
+            Alquil::Fenil => {
+                let b = self.afegeix(R::Benze, pos);
+                // El port que mira cap enrere
+                let graus = (-d.1).atan2(-d.0).to_degrees();
+                let port = ((graus + 90.0) / 60.0).round().rem_euclid(6.0) as usize;
+                (b, Some(port))
+            }
+        }
+    }
+
+    /// Tria els ports que falten: cada enllaç surt pel port lliure que mira més cap a l'altre
+    /// bloc
+    pub(crate) fn estructura(self) -> Result<Structure, ParseError> {
+        let massa = |i: usize| ParseError::Valence(self.radicals[i].to_string());
+        let direccions: Vec<_> = self
+            .radicals
+            .iter()
+            .map(|r| direccions_dels_ports(*r))
+            .collect();
+        let mut lliures: Vec<Vec<bool>> = direccions.iter().map(|d| vec![true; d.len()]).collect();
+        for &(a, b) in &self.enllacos {
+            for (i, port) in [a, b] {
+                if let Some(p) = port {
+                    match lliures[i].get_mut(p) {
+                        Some(lliure @ true) => *lliure = false,
+                        _ => return Err(massa(i)),
+                    }
+                }
+            }
+        }
+
+        let mut links = vec![];
+        for &((a, pa), (b, pb)) in &self.enllacos {
+            let mut tria = |i: usize, j: usize, port: Option<usize>| {
+                if let Some(p) = port {
+                    return Ok(p);
+                }
+                let (dx, dy) = (
+                    self.posicions[j].0 - self.posicions[i].0,
+                    self.posicions[j].1 - self.posicions[i].1,
+                );
+                let p = (0..direccions[i].len())
+                    .filter(|p| lliures[i][*p])
+                    .max_by(|p, q| {
+                        let cap = |k: &usize| direccions[i][*k].0 * dx + direccions[i][*k].1 * dy;
+                        cap(p).total_cmp(&cap(q))
+                    })
+                    .ok_or_else(|| massa(i))?;
+                lliures[i][p] = false;
+                Ok(p)
+            };
+            let pa = tria(a, b, pa)?;
+            let pb = tria(b, a, pb)?;
+            links.push(((a, pa), (b, pb)));
+        }
+        Ok(Structure {
+            molecule: Molecule::from_links(&self.radicals, &links),
+            positions: self.posicions,
+        })
+    }
+}
+
+/// Perquè els costats de l'anell facin 1
+fn radi_d_anell(n: usize) -> f32 {
+    0.5 / (std::f32::consts::PI / n as f32).sin()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Edition, Language, anomena_molecula, son_equivalents};
+
+    #[test]
+    fn de_nom_a_molecula() {
+        let s = parse_name("3-metilbutan-2-ol", &NamingOptions::default()).unwrap();
+        let esperada = Molecule::de(
+            &[R::C, R::C, R::C, R::C, R::Alcohol, R::C],
+            &[(0, 1, 1), (1, 2, 1), (2, 3, 1), (1, 4, 1), (2, 5, 1)],
+        );
+        assert!(son_equivalents(&s.molecule, &esperada));
+        // Els grups enllaçats queden a una llargada d'enllaç
+        assert_eq!(s.positions.len(), s.molecule.groups.len());
+        for b in &s.molecule.bonds {
+            let ((x0, y0), (x1, y1)) = (s.positions[b.from.0], s.positions[b.to.0]);
+            let d = ((x1 - x0).powi(2) + (y1 - y0).powi(2)).sqrt();
+            assert!((d - 1.0).abs() < 0.01, "{d}");
+        }
+    }
+
+    /// El nom que dona `anomena_molecula` a la molècula que llegim de `nom`
+    fn anada_i_tornada(nom: &str, opts: &NamingOptions) -> String {
+        let s = parse_name(nom, opts).unwrap_or_else(|e| panic!("{nom}: {e:?}"));
+        anomena_molecula(&s.molecule, opts)
+            .unwrap_or_else(|e| panic!("{nom}: {e:?}"))
+            .name
+    }
+
+    #[test]
+    fn els_noms_tornen_iguals() {
+        let opts = NamingOptions::default();
+        for nom in [
+            "2,3-dimetilpentà",
+            "pent-2-è",
+            "hexa-1,3-diè",
+            "but-3-in-2-ol",
+            "3-cloro-2-hidroxibutanal",
+            "etanoat de metil",
+            "propanoat d'etil",
+            "metoxietà",
+            "4-(1-metiletil)heptà",
+            "ciclohexanol",
+            "1,2-diclorobenzè",
+            "butan-1,3-diol",
+            "hexan-2,4-diona",
+            "propanonitril",
+        ] {
+            assert_eq!(anada_i_tornada(nom, &opts), nom);
+        }
+        let curts = NamingOptions {
+            short_substituent_names: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            anada_i_tornada("4-isopropilheptà", &curts),
+            "4-isopropilheptà"
+        );
+        let antiga = NamingOptions {
+            edition: Edition::Iupac1979,
+            ..Default::default()
+        };
+        assert_eq!(anada_i_tornada("2-butanol", &antiga), "2-butanol");
+        for (language, nom) in [
+            (Language::Spanish, "propanoato de etilo"),
+            (Language::English, "ethyl propanoate"),
+            (Language::English, "2,3-dimethylpentane"),
+        ] {
+            let opts = NamingOptions {
+                language,
+                ..Default::default()
+            };
+            assert_eq!(anada_i_tornada(nom, &opts), nom);
+        }
+    }
+
+    #[test]
+    fn localitzadors_que_falten() {
+        let opts = NamingOptions::default();
+        // Sense localitzadors, si només hi ha una molècula que es digui així
+        assert_eq!(anada_i_tornada("metilbutà", &opts), "2-metilbutà");
+        assert_eq!(anada_i_tornada("dimetilpropà", &opts), "2,2-dimetilpropà");
+        // I on no calen, el nom ja és sencer
+        assert_eq!(anada_i_tornada("butanona", &opts), "butanona");
+        assert_eq!(anada_i_tornada("metilciclopentà", &opts), "metilciclopentà");
+    }
+
+    #[test]
+    fn noms_que_no_son_els_preferits() {
+        // Localitzadors de més, una numeració que no és la més baixa, prefixos fora d'ordre i
+        // anells que no són el pare que tria el nomenador: la molècula és la mateixa
+        let d = NamingOptions::default();
+        let nova = NamingOptions {
+            edition: Edition::Iupac2013,
+            ..Default::default()
+        };
+        for (nom, bo) in [
+            ("propan-2-ona", "propanona"),
+            ("butan-2-ona", "butanona"),
+            ("etan-1-ol", "etanol"),
+            ("1-clorometà", "clorometà"),
+            ("ciclohexan-1-ol", "ciclohexanol"),
+            ("ciclohex-1-è", "ciclohexè"),
+            ("pent-4-è", "pent-1-è"),
+            ("2-metil-3-etilhexà", "3-etil-2-metilhexà"),
+        ] {
+            assert_eq!(anada_i_tornada(nom, &d), bo);
+            assert_eq!(anada_i_tornada(nom, &nova), bo);
+        }
+        assert_eq!(anada_i_tornada("hexilciclopropà", &d), "1-ciclopropilhexà");
+        // Els que una edició anomena així i l'altra no
+        assert_eq!(anada_i_tornada("2-etilbut-1-è", &nova), "3-metilidenpentà");
+        assert_eq!(anada_i_tornada("3-metilidenpentà", &d), "2-etilbut-1-è");
+    }
+
+    #[test]
+    fn errors() {
+        let opts = NamingOptions::default();
+        let error = |nom| parse_name(nom, &opts).err();
+        assert_eq!(error("  "), Some(ParseError::Empty));
+        assert!(matches!(error("xyzà"), Some(ParseError::Unknown(_))));
+        assert!(matches!(
+            error("2-dimetilpropà"),
+            Some(ParseError::LocantCount(_))
+        ));
+        assert_eq!(error("5-metilbutà"), Some(ParseError::InvalidLocant(5)));
+        // Localitzadors que allargarien la cadena
+        assert_eq!(error("5-metilpentà"), Some(ParseError::InvalidLocant(5)));
+        assert_eq!(error("2-etilpentà"), Some(ParseError::InvalidLocant(2)));
+        assert_eq!(error("1-etilpropà"), Some(ParseError::InvalidLocant(1)));
+        // Una cetona a l'extrem és un aldehid
+        assert_eq!(error("butan-1-ona"), Some(ParseError::InvalidLocant(1)));
+        assert_eq!(error("1-oxobutà"), Some(ParseError::InvalidLocant(1)));
+        // L'1-clorobutà i el 2-clorobutà; i massa maneres de posar-los
+        assert_eq!(error("clorobutà"), Some(ParseError::MissingLocants));
+        assert_eq!(
+            error("pentametilpentadecà"),
+            Some(ParseError::MissingLocants)
+        );
+        // El metilciclopropà
+        assert!(matches!(
+            error("ciclopropilmetà"),
+            Some(ParseError::Unknown(_))
+        ));
+        assert!(matches!(
+            error("2,2,2,2-tetrametilpropà"),
+            Some(ParseError::Valence(_))
+        ));
+    }
+}
@@ -0,0 +1,133 @@
+//! Noms propis de compostos habituals. Es reconeixen amb `son_equivalents`, així que no importa
+//! com s'hagin dibuixat
+use super::{EnllacDeBlocs, normalitza};
+use crate::UiRadical::{self, *};
+use crate::{Language, Molecule, son_equivalents};
+
+/// (nom en català, castellà i anglès, blocs, enllaços entre blocs), tal com els dibuixaria
+/// l'usuari. El port 0 d'un èster és el de l'acil, i els d'un benzè són els seus carbonis en ordre
+type Patro = (
+    [&'static str; 3],
+    &'static [UiRadical],
+    &'static [EnllacDeBlocs],
+);
+
+const NOMS_PROPIS: &[Patro] = &[
+    (
+        ["àcid fòrmic", "ácido fórmico", "formic acid"],
+        &[Carboxil],
+        &[],
+    ),
+    (
+        ["àcid acètic", "ácido acético", "acetic acid"],
+        &[C, Carboxil],
+        &[((0, 0), (1, 0))],
+    ),
+    (
+        ["àcid oxàlic", "ácido oxálico", "oxalic acid"],
+        &[Carboxil, Carboxil],
+        &[((0, 0), (1, 0))],
+    ),
+    (
+        ["formaldehid", "formaldehído", "formaldehyde"],
+        &[Aldehid],
+        &[],
+    ),
+    (
+        ["acetaldehid", "acetaldehído", "acetaldehyde"],
+        &[C, Aldehid],
+        &[((0, 0), (1, 0))],
+    ),
+    (
+        ["acetona", "acetona", "acetone"],
+        &[C, Cetona, C],
+        &[((0, 0), (1, 0)), ((1, 1), (2, 0))],
+    ),
+    (
+        ["acetonitril", "acetonitrilo", "acetonitrile"],
+        &[C, Nitril],
+        &[((0, 0), (1, 0))],
+    ),
+    (
+        ["etilè", "etileno", "ethylene"],
+        &[C, C],
+        &[((0, 0), (1, 0)), ((0, 1), (1, 1))],
+    ),
+    (
+        ["acetilè", "acetileno", "acetylene"],
+        &[C, C],
+        &[((0, 0), (1, 0)), ((0, 1), (1, 1)), ((0, 2), (1, 2))],
+    ),
+    (
+        ["cloroform", "cloroformo", "chloroform"],
+        &[C, Cl, Cl, Cl],
+        &[((0, 0), (1, 0)), ((0, 1), (2, 0)), ((0, 2), (3, 0))],
+    ),
+    (
+        ["etilenglicol", "etilenglicol", "ethylene glycol"],
+        &[Alcohol, C, C, Alcohol],
+        &[((0, 0), (1, 0)), ((1, 1), (2, 0)), ((2, 1), (3, 0))],
+    ),
+    (
+        ["glicerina", "glicerina", "glycerol"],
+        &[Alcohol, C, C, C, Alcohol, Alcohol],
+        &[
+            ((0, 0), (1, 0)),
+            ((1, 1), (2, 0)),
+            ((2, 1), (3, 0)),
+            ((3, 1), (4, 0)),
+            ((2, 2), (5, 0)),
+        ],
+    ),
+    (
+        ["acetat d'etil", "acetato de etilo", "ethyl acetate"],
+        &[C, Ester, C, C],
+        &[((0, 0), (1, 0)), ((1, 1), (2, 0)), ((2, 1), (3, 0))],
+    ),
+    (
+        ["toluè", "tolueno", "toluene"],
+        &[Benze, C],
+        &[((0, 0), (1, 0))],
+    ),
+    (
+        ["estirè", "estireno", "styrene"],
+        &[Benze, C, C],
+        &[((0, 0), (1, 0)), ((1, 1), (2, 0)), ((1, 2), (2, 1))],
+    ),
+    (
+        ["o-xilè", "o-xileno", "o-xylene"],
+        &[Benze, C, C],
+        &[((0, 0), (1, 0)), ((0, 1), (2, 0))],
+    ),
+    (
+        ["m-xilè", "m-xileno", "m-xylene"],
+        &[Benze, C, C],
+        &[((0, 0), (1, 0)), ((0, 2), (2, 0))],
+    ),
+    (
+        ["p-xilè", "p-xileno", "p-xylene"],
+        &[Benze, C, C],
+        &[((0, 0), (1, 0)), ((0, 3), (2, 0))],
+    ),
+];
+
+/// Els blocs i els enllaços del compost amb aquest nom propi, ja normalitzat
+pub(super) fn patro(
+    nom: &str,
+    idioma: Language,
+) -> Option<(&'static [UiRadical], &'static [EnllacDeBlocs])> {
+    NOMS_PROPIS
+        .iter()
+        .find(|(noms, ..)| normalitza(noms[idioma as usize]) == nom)
+        .map(|(_, radicals, enllacos)| (*radicals, *enllacos))
+}
+
+/// El nom propi de la molècula, si en té
+pub(super) fn nom_propi(molecula: &Molecule, idioma: Language) -> Option<&'static str> {
+    NOMS_PROPIS
+        .iter()
+        .find(|(_, radicals, enllacos)| {
+            son_equivalents(molecula, &Molecule::from_links(radicals, enllacos))
+        })
+        .map(|(noms, ..)| noms[idioma as usize])
+}
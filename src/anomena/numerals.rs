@@ -0,0 +1,100 @@
+//! Els prefixos numerals de la IUPAC: "undeca", "icosa", "henicosa", "triaconta", "hecta"...
+//! Es formen igual en tots els idiomes, de les unitats a les centenes: 123 és "tricosahecta"
+
+/// El nombre més gran que sabem dir
+pub(super) const MAXIM: usize = 999;
+
+const UNITATS: [&str; 10] = [
+    "", "hen", "do", "tri", "tetra", "penta", "hexa", "hepta", "octa", "nona",
+];
+const DESENES: [&str; 10] = [
+    "",
+    "deca",
+    "icosa",
+    "triaconta",
+    "tetraconta",
+    "pentaconta",
+    "hexaconta",
+    "heptaconta",
+    "octaconta",
+    "nonaconta",
+];
+const CENTENES: [&str; 10] = [
+    "", "hecta", "dicta", "tricta", "tetracta", "pentacta", "hexacta", "heptacta", "octacta",
+    "nonacta",
+];
+
+/// El prefix numeral de `n`, com "tetra", "undeca", "docosa" o "hentriaconta"
+pub(super) fn numeral(n: usize) -> Option<String> {
+    if !(1..=MAXIM).contains(&n) {
+        return None;
+    }
+    let (u, d, c) = (n % 10, n / 10 % 10, n / 100);
+    let mut s = match (u, d, c) {
+        (1, 0, 0) => "mono",
+        (2, 0, 0) => "di",
+        (1, 1, _) => "un",
+        _ => UNITATS[u],
+    }
+    .to_string();
+    // L'"i" d'"icosa" cau darrere vocal: "docosa", "tricosa", però "henicosa"
+    match DESENES[d] {
+        "icosa" if s.ends_with(['a', 'i', 'o']) => s.push_str("cosa"),
+        desenes => s.push_str(desenes),
+    }
+    s.push_str(CENTENES[c]);
+    Some(s)
+}
+
+/// L'arrel de les cadenes de 5 o més carbonis: "pent", "undec", "icos", "hect"
+pub(super) fn arrel(n: usize) -> Option<String> {
+    let mut s = numeral(n)?;
+    s.pop();
+    Some(s)
+}
+
+/// El multiplicador de `n` substituents iguals. Els `compostos`, que van entre parèntesis, fan
+/// servir "bis", "tris", "tetrakis"...
+pub(super) fn multiplicador(n: usize, compostos: bool) -> String {
+    match (n, compostos) {
+        (1, _) => String::new(),
+        (2, true) => "bis".to_string(),
+        (3, true) => "tris".to_string(),
+        (n, true) => numeral(n).map_or_else(|| "?".to_string(), |s| s + "kis"),
+        (n, false) => numeral(n).unwrap_or_else(|| "?".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numerals() {
+        let noms = [1, 2, 4, 11, 20, 21, 22, 30, 31, 100, 123].map(|n| numeral(n).unwrap());
+        assert_eq!(
+            noms,
+            [
+                "mono",
+                "di",
+                "tetra",
+                "undeca",
+                "icosa",
+                "henicosa",
+                "docosa",
+                "triaconta",
+                "hentriaconta",
+                "hecta",
+                "tricosahecta"
+            ]
+        );
+        assert_eq!(numeral(0), None);
+        assert_eq!(numeral(MAXIM + 1), None);
+        assert_eq!(arrel(21).as_deref(), Some("henicos"));
+        assert_eq!(multiplicador(1, true), "");
+        assert_eq!(multiplicador(2, false), "di");
+        assert_eq!(multiplicador(2, true), "bis");
+        assert_eq!(multiplicador(3, true), "tris");
+        assert_eq!(multiplicador(4, true), "tetrakis");
+    }
+}
@@ -0,0 +1,718 @@
+//! La molècula àtom per àtom, per als formats que no saben res dels blocs, com el SMILES o el
+//! molfile. Els grups es despleguen en àtoms, i els àtoms es tornen a plegar en grups
+use crate::{Bond, Molecule, ParseError, Structure, UiRadical as R, anomena::Molecula};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Element {
+    C,
+    H,
+    N,
+    O,
+    S,
+    F,
+    Cl,
+    Br,
+    I,
+}
+
+impl Element {
+    /// Tots menys l'H, que no es dibuixa: cada àtom du els seus
+    pub(crate) const TOTS: [Element; 8] = [
+        Element::C,
+        Element::N,
+        Element::O,
+        Element::S,
+        Element::F,
+        Element::Cl,
+        Element::Br,
+        Element::I,
+    ];
+
+    pub(crate) fn simbol(self) -> &'static str {
+        match self {
+            Self::C => "C",
+            Self::H => "H",
+            Self::N => "N",
+            Self::O => "O",
+            Self::S => "S",
+            Self::F => "F",
+            Self::Cl => "Cl",
+            Self::Br => "Br",
+            Self::I => "I",
+        }
+    }
+
+    /// Quants enllaços fa, comptant-hi els H
+    pub(crate) fn valencia(self) -> usize {
+        match self {
+            Self::C => 4,
+            Self::N => 3,
+            Self::O | Self::S => 2,
+            Self::H | Self::F | Self::Cl | Self::Br | Self::I => 1,
+        }
+    }
+}
+
+/// L'ordre dels enllaços aromàtics, que no és cap dels altres
+pub(crate) const AROMATIC: usize = 4;
+
+/// Els àtoms i els enllaços entre ells. Els H no hi són: cada àtom en du `h`
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Atoms {
+    pub elements: Vec<Element>,
+    pub h: Vec<usize>,
+    /// (veí, ordre) de cada àtom
+    pub adj: Vec<Vec<(usize, usize)>>,
+}
+
+impl Atoms {
+    pub(crate) fn afegeix(&mut self, e: Element, h: usize) -> usize {
+        self.elements.push(e);
+        self.h.push(h);
+        self.adj.push(vec![]);
+        self.elements.len() - 1
+    }
+
+    pub(crate) fn enllaca(&mut self, a: usize, b: usize, ordre: usize) {
+        for (x, y) in [(a, b), (b, a)] {
+            match self.adj[x].iter_mut().find(|(v, _)| *v == y) {
+                Some((_, o)) => *o += ordre,
+                None => self.adj[x].push((y, ordre)),
+            }
+        }
+    }
+
+    pub(crate) fn ordre(&self, a: usize, b: usize) -> usize {
+        self.adj[a]
+            .iter()
+            .find(|(v, _)| *v == b)
+            .map_or(0, |(_, o)| *o)
+    }
+
+    /// Els enllaços que fa, comptant-ne l'ordre però no els H
+    pub(crate) fn enllacos(&self, a: usize) -> usize {
+        self.adj[a].iter().map(|(_, o)| o).sum()
+    }
+
+    /// Els anells de sis carbonis amb enllaços simples i dobles, cada un en l'ordre en què es
+    /// recorre
+    fn anells_de_sis(&self) -> Vec<[usize; 6]> {
+        let mut anells: Vec<[usize; 6]> = vec![];
+        let es_c = |i: usize| self.elements[i] == Element::C;
+        for inici in (0..self.elements.len()).filter(|&i| es_c(i)) {
+            // Camins des d'`inici` per àtoms més grans, perquè cada anell surti només des del
+            // seu àtom més petit
+            let mut pila = vec![vec![inici]];
+            while let Some(cami) = pila.pop() {
+                let ultim = *cami.last().expect("path is never empty");
+                for &(v, o) in &self.adj[ultim] {
+                    if !(1..=2).contains(&o) {
+                        continue;
+                    }
+                    if cami.len() == 6 {
+                        if v == inici {
+                            let anell: [usize; 6] = cami.clone().try_into().expect("six atoms");
+                            // Cada anell surt en els dos sentits
+                            let girat = |a: &[usize; 6]| (1..6).all(|k| a[k] == anell[6 - k]);
+                            if !anells.iter().any(girat) {
+                                anells.push(anell);
+                            }
+                        }
+                    } else if v > inici && es_c(v) && !cami.contains(&v) {
+                        pila.push([&cami[..], &[v]].concat());
+                    }
+                }
+            }
+        }
+        anells
+    }
+
+    /// Els anells de sis carbonis amb els enllaços alternats, els benzens, cada un en l'ordre
+    /// en què es recorre
+    pub(crate) fn benzens(&self) -> Vec<[usize; 6]> {
+        let mut anells = self.anells_de_sis();
+        anells.retain(|anell| {
+            (0..6).all(|k| {
+                let ordre = |k: usize| self.ordre(anell[k % 6], anell[(k + 1) % 6]);
+                ordre(k) != ordre(k + 1)
+            })
+        });
+        anells
+    }
+
+    /// Passa els enllaços dels anells aromàtics a `AROMATIC`, perquè siguin els mateixos
+    /// s'escriguin com s'escriguin, i diu quins àtoms hi són. Un anell és aromàtic si cada
+    /// àtom fa un doble enllaç dins d'algun dels anells aromàtics: així un naftalè ho és tot
+    /// sencer, encara que un dels seus anells no tengui els dobles enllaços alternats
+    pub(crate) fn aromatitza(&mut self) -> Vec<bool> {
+        let mut anells = self.anells_de_sis();
+        loop {
+            let enllacos: Vec<(usize, usize)> = anells
+                .iter()
+                .flat_map(|anell| (0..6).map(|k| (anell[k], anell[(k + 1) % 6])))
+                .collect();
+            let de_l_anell =
+                |a: usize, b: usize| enllacos.contains(&(a, b)) || enllacos.contains(&(b, a));
+            let abans = anells.len();
+            anells.retain(|anell| {
+                anell.iter().all(|&a| {
+                    let mut dobles = self.adj[a].iter().filter(|(_, o)| *o == 2);
+                    match (dobles.next(), dobles.next()) {
+                        (Some(&(v, _)), None) => de_l_anell(a, v),
+                        _ => false,
+                    }
+                })
+            });
+            if anells.len() == abans {
+                break;
+            }
+        }
+        let mut aromatic = vec![false; self.elements.len()];
+        for anell in anells {
+            for k in 0..6 {
+                let (a, b) = (anell[k], anell[(k + 1) % 6]);
+                aromatic[a] = true;
+                for (x, y) in [(a, b), (b, a)] {
+                    if let Some((_, o)) = self.adj[x].iter_mut().find(|(v, _)| *v == y) {
+                        *o = AROMATIC;
+                    }
+                }
+            }
+        }
+        aromatic
+    }
+
+    /// Classifica els àtoms per com són (quants veïns, quin element...) i després per com són
+    /// els veïns, fins que no en surten més classes. Dos àtoms que poden ser l'un l'altre (els
+    /// dos CH3 del propà) queden a la mateixa classe
+    pub(crate) fn classes_equivalents(&self, aromatic: &[bool]) -> Vec<usize> {
+        let invariants: Vec<_> = (0..self.elements.len())
+            .map(|i| {
+                (
+                    self.adj[i].len(),
+                    self.elements[i],
+                    aromatic[i],
+                    self.enllacos(i),
+                    self.h[i],
+                )
+            })
+            .collect();
+        self.refina(classifica(&invariants))
+    }
+
+    /// Separa les classes pels veïns fins que no canvien
+    pub(crate) fn refina(&self, mut rang: Vec<usize>) -> Vec<usize> {
+        loop {
+            let claus: Vec<_> = (0..self.elements.len())
+                .map(|i| {
+                    let mut veins: Vec<_> =
+                        self.adj[i].iter().map(|&(v, o)| (rang[v], o)).collect();
+                    veins.sort();
+                    (rang[i], veins)
+                })
+                .collect();
+            let nou = classifica(&claus);
+            if classes(&nou) == classes(&rang) {
+                return rang;
+            }
+            rang = nou;
+        }
+    }
+}
+
+/// La posició de cada valor entre els valors diferents, ordenats
+pub(crate) fn classifica<T: Ord>(valors: &[T]) -> Vec<usize> {
+    let mut ordenats: Vec<&T> = valors.iter().collect();
+    ordenats.sort();
+    ordenats.dedup();
+    valors
+        .iter()
+        .map(|v| ordenats.binary_search(&v).expect("value is there"))
+        .collect()
+}
+
+/// Quantes classes hi ha
+pub(crate) fn classes(rang: &[usize]) -> usize {
+    rang.iter().max().map_or(0, |r| r + 1)
+}
+
+/// Un àtom tal com es llegeix: l'element, si és aromàtic i, si es diu, quants H té
+pub(crate) type AtomLlegit = (Element, bool, Option<usize>);
+
+/// Un enllaç tal com es llegeix: els dos àtoms i l'ordre, o cap si és aromàtic
+pub(crate) type EnllacLlegit = (usize, usize, Option<usize>);
+
+/// Per què els àtoms llegits no fan una molècula
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Problema {
+    /// Un àtom amb massa enllaços
+    Valencia(Element),
+    /// Un àtom amb menys enllaços i H dels que fa
+    MigFer(Element),
+    /// Un anell aromàtic sense enllaços dobles possibles
+    Aromaticitat,
+    /// Dos enllaços entre els mateixos àtoms, o un àtom enllaçat amb ell mateix
+    EnllacRepetit,
+}
+
+/// Fa els àtoms a partir del que diu un format: els H que no es diuen són els que falten, i
+/// els enllaços aromàtics (els que no tenen ordre) es fan simples i dobles
+pub(crate) fn completa(atoms: &[AtomLlegit], enllacos: &[EnllacLlegit]) -> Result<Atoms, Problema> {
+    let n = atoms.len();
+    // Els enllaços aromàtics compten com a simples fins que es triïn els dobles
+    let aromatics: Vec<(usize, usize)> = enllacos
+        .iter()
+        .filter(|(.., o)| o.is_none())
+        .map(|&(a, b, _)| (a, b))
+        .collect();
+    let mut fets = vec![0; n];
+    for &(a, b, o) in enllacos {
+        fets[a] += o.unwrap_or(1);
+        fets[b] += o.unwrap_or(1);
+    }
+    let mut h = vec![0; n];
+    let mut vol_doble = vec![false; n];
+    for (i, &(e, aromatic, explicits)) in atoms.iter().enumerate() {
+        let lliures = e.valencia() as isize - fets[i] as isize;
+        h[i] = match explicits {
+            Some(h) => h,
+            // Un aromàtic en guarda un per al doble enllaç
+            None => (lliures - isize::from(aromatic)).max(0) as usize,
+        };
+        match lliures - h[i] as isize {
+            0 => {}
+            1 if aromatic => vol_doble[i] = true,
+            d if d < 0 => return Err(Problema::Valencia(e)),
+            _ => return Err(Problema::MigFer(e)),
+        }
+    }
+    let dobles = kekule(&aromatics, &vol_doble).ok_or(Problema::Aromaticitat)?;
+
+    let mut resultat = Atoms::default();
+    for (i, &(e, ..)) in atoms.iter().enumerate() {
+        resultat.afegeix(e, h[i]);
+    }
+    for &(a, b, o) in enllacos {
+        if a == b || resultat.ordre(a, b) > 0 {
+            return Err(Problema::EnllacRepetit);
+        }
+        let ordre = match o {
+            Some(o) => o,
+            None if dobles.contains(&(a, b)) => 2,
+            None => 1,
+        };
+        resultat.enllaca(a, b, ordre);
+    }
+    Ok(resultat)
+}
+
+/// Tria quins enllaços aromàtics són dobles perquè cada àtom que en vol un en tingui un
+fn kekule(aromatics: &[(usize, usize)], vol_doble: &[bool]) -> Option<Vec<(usize, usize)>> {
+    fn prova(
+        aromatics: &[(usize, usize)],
+        falta: &mut [bool],
+        dobles: &mut Vec<(usize, usize)>,
+    ) -> bool {
+        let Some(i) = falta.iter().position(|f| *f) else {
+            return true;
+        };
+        for &(a, b) in aromatics {
+            let altre = match (a == i, b == i) {
+                (true, _) => b,
+                (_, true) => a,
+                _ => continue,
+            };
+            if !falta[altre] {
+                continue;
+            }
+            falta[i] = false;
+            falta[altre] = false;
+            dobles.push((a, b));
+            if prova(aromatics, falta, dobles) {
+                return true;
+            }
+            dobles.pop();
+            falta[i] = true;
+            falta[altre] = true;
+        }
+        false
+    }
+    let mut falta = vol_doble.to_vec();
+    let mut dobles = vec![];
+    prova(aromatics, &mut falta, &mut dobles).then_some(dobles)
+}
+
+/// Un enllaç dins d'un grup: (àtom, àtom, ordre)
+type EnllacIntern = (usize, usize, usize);
+
+/// Els àtoms d'un grup, els enllaços entre ells i quin àtom hi ha darrere de cada port
+fn desplegament(
+    r: R,
+) -> (
+    &'static [Element],
+    &'static [EnllacIntern],
+    &'static [usize],
+) {
+    use Element::*;
+    match r {
+        R::C => (&[C], &[], &[0, 0, 0, 0]),
+        R::F => (&[F], &[], &[0]),
+        R::Cl => (&[Cl], &[], &[0]),
+        R::Br => (&[Br], &[], &[0]),
+        R::I => (&[I], &[], &[0]),
+        R::H => (&[H], &[], &[0]),
+        R::N => (&[N], &[], &[0, 0, 0]),
+        R::O => (&[O], &[], &[0, 0]),
+        R::S => (&[S], &[], &[0, 0]),
+        R::Amina => (&[N], &[], &[0]),
+        R::Eter => (&[O], &[], &[0, 0]),
+        R::Alcohol => (&[O], &[], &[0]),
+        // Els dobles enllaços com a `Graf::de_blocs`
+        R::Benze => (
+            &[C; 6],
+            &[
+                (0, 1, 2),
+                (1, 2, 1),
+                (2, 3, 2),
+                (3, 4, 1),
+                (4, 5, 2),
+                (5, 0, 1),
+            ],
+            &[0, 1, 2, 3, 4, 5],
+        ),
+        R::Cetona => (&[C, O], &[(0, 1, 2)], &[0, 0]),
+        R::Aldehid => (&[C, O], &[(0, 1, 2)], &[0]),
+        R::Nitril => (&[C, N], &[(0, 1, 3)], &[0]),
+        R::Amida => (&[C, O, N], &[(0, 1, 2), (0, 2, 1)], &[0]),
+        R::Ester => (&[C, O, O], &[(0, 1, 2), (0, 2, 1)], &[0, 2]),
+        R::Carboxil => (&[C, O, O], &[(0, 1, 2), (0, 2, 1)], &[0]),
+    }
+}
+
+/// Els àtoms de la molècula i, de cada un, el grup i quin àtom del grup és. Els enllaços buits
+/// se consideren H, i els blocs d'H es compten com els H de l'àtom on són
+pub(crate) fn desplega(m: &Molecule) -> (Atoms, Vec<(usize, usize)>) {
+    let mut atoms = Atoms::default();
+    let mut de = vec![];
+    let mut ports = vec![];
+    for (g, &r) in m.groups.iter().enumerate() {
+        let (elements, enllacos, de_port) = desplegament(r);
+        let primer = atoms.elements.len();
+        for (k, &e) in elements.iter().enumerate() {
+            atoms.afegeix(e, 0);
+            de.push((g, k));
+        }
+        for &(a, b, o) in enllacos {
+            atoms.enllaca(primer + a, primer + b, o);
+        }
+        ports.push(de_port.iter().map(|a| primer + a).collect::<Vec<_>>());
+    }
+    for b in &m.bonds {
+        let atom = |(g, p): (usize, usize)| ports[g].get(p).copied().unwrap_or(ports[g][0]);
+        atoms.enllaca(atom(b.from), atom(b.to), b.order);
+    }
+    // Sense els H, els seus enllaços queden buits
+    let pesants: Vec<usize> = (0..atoms.elements.len())
+        .filter(|&i| atoms.elements[i] != Element::H)
+        .collect();
+    let mut sense_h = Atoms::default();
+    for &i in &pesants {
+        sense_h.afegeix(atoms.elements[i], 0);
+    }
+    for (a, &i) in pesants.iter().enumerate() {
+        for &(j, o) in &atoms.adj[i] {
+            if let Ok(b) = pesants.binary_search(&j)
+                && a < b
+            {
+                sense_h.enllaca(a, b, o);
+            }
+        }
+    }
+    for i in 0..sense_h.elements.len() {
+        sense_h.h[i] = sense_h.elements[i]
+            .valencia()
+            .saturating_sub(sense_h.enllacos(i));
+    }
+    let de = pesants.iter().map(|&i| de[i]).collect();
+    (sense_h, de)
+}
+
+/// Un extrem d'un enllaç entre grups: el grup i, si el grup en té de diferents, el port
+pub(crate) type ExtremDeGrup = (usize, Option<usize>);
+
+/// Els àtoms plegats en grups
+#[derive(Debug, Default)]
+pub(crate) struct Plegat {
+    pub grups: Vec<R>,
+    /// (extrem, extrem, ordre) de cada enllaç entre grups
+    pub enllacos: Vec<(ExtremDeGrup, ExtremDeGrup, usize)>,
+    /// El grup de cada àtom
+    pub de: Vec<usize>,
+}
+
+impl Plegat {
+    /// Els veïns de cada grup
+    pub(crate) fn veins(&self) -> Vec<Vec<usize>> {
+        let mut veins = vec![vec![]; self.grups.len()];
+        for &((a, _), (b, _), _) in &self.enllacos {
+            veins[a].push(b);
+            veins[b].push(a);
+        }
+        veins
+    }
+
+    /// Els blocs, amb els grups a `posicions` (en llargades d'enllaç, com a `Structure`). Els
+    /// ports que no s'han triat surten cap a l'altre grup
+    pub(crate) fn estructura(&self, posicions: &[(f32, f32)]) -> Result<Structure, ParseError> {
+        let mut m = Molecula::default();
+        for (&r, &pos) in self.grups.iter().zip(posicions) {
+            m.afegeix(r, pos);
+        }
+        for &(a, b, ordre) in &self.enllacos {
+            m.enllaca(a, b, ordre);
+        }
+        m.estructura()
+    }
+
+    /// La molècula, sense posicions. Els ports que no s'han triat són els primers lliures
+    pub(crate) fn molecula(&self) -> Molecule {
+        let mut m = Molecule {
+            groups: self.grups.clone(),
+            bonds: vec![],
+        };
+        let mut seguent = vec![0; self.grups.len()];
+        let mut port = |(g, p): ExtremDeGrup| match p {
+            Some(p) => p,
+            None => {
+                seguent[g] += 1;
+                seguent[g] - 1
+            }
+        };
+        for &(a, b, ordre) in &self.enllacos {
+            let (a, b) = ((a.0, port(a)), (b.0, port(b)));
+            m.bonds.push(Bond {
+                from: a,
+                to: b,
+                order: ordre,
+            });
+        }
+        m
+    }
+}
+
+/// La molècula amb els àtoms sols (els blocs d'H, N, O, S) plegats en els grups que formen: un
+/// C i un O enllaçats amb un doble enllaç són un `Cetona` o un `Aldehid`, i així. El nomenador
+/// només sap anomenar grups, i passa per aquí quan hi ha àtoms sols
+pub fn agrupa_molecula(molecula: &Molecule) -> Molecule {
+    let (atoms, _) = desplega(molecula);
+    plega(&atoms).molecula()
+}
+
+/// Torna a fer grups amb els àtoms: els benzens, els grups amb un carbonil (COOH, COO, CONH2,
+/// CHO, CO), els nitrils, i després els O (OH, -O-), els N (NH2) i els carbonis que quedin.
+/// Els àtoms que no fan cap grup queden sols
+pub(crate) fn plega(a: &Atoms) -> Plegat {
+    use Element as E;
+    let n = a.elements.len();
+    // El grup de cada àtom i, si importa, el port
+    let mut de: Vec<Option<ExtremDeGrup>> = vec![None; n];
+    let mut grups = vec![];
+    let mut nou = |r: R, atoms: &[(usize, Option<usize>)], de: &mut Vec<Option<ExtremDeGrup>>| {
+        for &(i, port) in atoms {
+            de[i] = Some((grups.len(), port));
+        }
+        grups.push(r);
+    };
+
+    for anell in a.benzens() {
+        if anell.iter().all(|&i| de[i].is_none()) {
+            let atoms: Vec<_> = (0..6).map(|k| (anell[k], Some(k))).collect();
+            nou(R::Benze, &atoms, &mut de);
+        }
+    }
+
+    // Un veí d'`i` que encara no és de cap grup, que no té més enllaços i que té els H donats
+    let terminal = |de: &[Option<ExtremDeGrup>], i: usize, e: E, ordre: usize, h: usize| {
+        a.adj[i].iter().map(|(v, _)| *v).find(|&v| {
+            de[v].is_none()
+                && a.elements[v] == e
+                && a.ordre(i, v) == ordre
+                && a.adj[v].len() == 1
+                && a.h[v] == h
+        })
+    };
+    for i in (0..n).filter(|&i| a.elements[i] == E::C) {
+        if de[i].is_some() {
+            continue;
+        }
+        if let Some(n) = terminal(&de, i, E::N, 3, 0) {
+            nou(R::Nitril, &[(i, None), (n, None)], &mut de);
+            continue;
+        }
+        let Some(o) = terminal(&de, i, E::O, 2, 0) else {
+            continue;
+        };
+        if let Some(oh) = terminal(&de, i, E::O, 1, 1) {
+            nou(R::Carboxil, &[(i, None), (o, None), (oh, None)], &mut de);
+        } else if let Some(nh2) = terminal(&de, i, E::N, 1, 2) {
+            nou(R::Amida, &[(i, None), (o, None), (nh2, None)], &mut de);
+        } else if let Some(&(eter, _)) = a.adj[i].iter().find(|&&(v, ordre)| {
+            de[v].is_none() && a.elements[v] == E::O && ordre == 1 && a.adj[v].len() == 2
+        }) {
+            nou(
+                R::Ester,
+                &[(i, Some(0)), (o, None), (eter, Some(1))],
+                &mut de,
+            );
+        } else if a.h[i] == 0 {
+            // Sense H el carbonil fa dos enllaços, com a HN=C=O o a H2C=C=O
+            nou(R::Cetona, &[(i, None), (o, None)], &mut de);
+        } else {
+            nou(R::Aldehid, &[(i, None), (o, None)], &mut de);
+        }
+    }
+
+    for i in 0..n {
+        if de[i].is_some() {
+            continue;
+        }
+        let senzills = a.adj[i].iter().all(|(_, o)| *o == 1);
+        let r = match (a.elements[i], a.adj[i].len()) {
+            (E::C, _) => R::C,
+            (E::F, _) => R::F,
+            (E::Cl, _) => R::Cl,
+            (E::Br, _) => R::Br,
+            (E::I, _) => R::I,
+            (E::O, 0 | 1) if senzills => R::Alcohol,
+            (E::O, 2) if senzills => R::Eter,
+            (E::N, 0 | 1) if senzills => R::Amina,
+            (E::O, _) => R::O,
+            (E::N, _) => R::N,
+            (E::S, _) => R::S,
+            (E::H, _) => R::H,
+        };
+        nou(r, &[(i, None)], &mut de);
+    }
+
+    let de: Vec<ExtremDeGrup> = de
+        .into_iter()
+        .map(|d| d.expect("every atom got a group"))
+        .collect();
+    let mut enllacos = vec![];
+    for i in 0..n {
+        for &(j, ordre) in &a.adj[i] {
+            if i < j && de[i].0 != de[j].0 {
+                enllacos.push((de[i], de[j], ordre));
+            }
+        }
+    }
+    Plegat {
+        grups,
+        enllacos,
+        de: de.iter().map(|(g, _)| *g).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use R::*;
+
+    #[test]
+    fn despleguen_els_grups() {
+        let (atoms, de) = desplega(&Molecule::de(&[C, Carboxil], &[(0, 1, 1)]));
+        use Element as E;
+        assert_eq!(atoms.elements, [E::C, E::C, E::O, E::O]);
+        assert_eq!(atoms.h, [3, 0, 0, 1]);
+        assert_eq!(atoms.ordre(1, 2), 2);
+        assert_eq!(de, [(0, 0), (1, 0), (1, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn es_pleguen_els_atoms() {
+        // CH3-C(=O)-O-CH2-CH3, àtom per àtom
+        let m = Molecule::de(
+            &[C, C, O, O, C, C],
+            &[(0, 1, 1), (1, 2, 2), (1, 3, 1), (3, 4, 1), (4, 5, 1)],
+        );
+        let plegada = agrupa_molecula(&m);
+        let mut grups = plegada.groups.clone();
+        grups.sort();
+        assert_eq!(grups, [C, C, C, Ester]);
+        assert!(crate::son_equivalents(&m, &plegada));
+    }
+
+    #[test]
+    fn benzens() {
+        let kekule = Molecule::de(
+            &[C; 6],
+            &[
+                (0, 1, 2),
+                (1, 2, 1),
+                (2, 3, 2),
+                (3, 4, 1),
+                (4, 5, 2),
+                (5, 0, 1),
+            ],
+        );
+        let (mut atoms, _) = desplega(&kekule);
+        assert_eq!(atoms.benzens().len(), 1);
+        assert_eq!(plega(&atoms).grups, [Benze]);
+        assert_eq!(atoms.aromatitza(), [true; 6]);
+
+        let (ciclohexa, _) = desplega(&Molecule::de(
+            &[C; 6],
+            &[
+                (0, 1, 1),
+                (1, 2, 1),
+                (2, 3, 1),
+                (3, 4, 1),
+                (4, 5, 1),
+                (5, 0, 1),
+            ],
+        ));
+        assert!(ciclohexa.benzens().is_empty());
+    }
+
+    #[test]
+    fn classes_equivalents() {
+        let (mut propa, _) = desplega(&Molecule::de(&[C, C, C], &[(0, 1, 1), (1, 2, 1)]));
+        let aromatic = propa.aromatitza();
+        let classe = propa.classes_equivalents(&aromatic);
+        assert_eq!(classe[0], classe[2]);
+        assert_ne!(classe[0], classe[1]);
+    }
+
+    #[test]
+    fn completa() {
+        use Element as E;
+        let anell = |n: usize| -> Vec<_> { (0..n).map(|i| (i, (i + 1) % n, None)).collect() };
+        let atoms = super::completa(&[(E::C, true, None); 6], &anell(6)).unwrap();
+        assert_eq!(atoms.h, [1; 6]);
+        assert_eq!(atoms.benzens().len(), 1);
+        assert_eq!(
+            super::completa(&[(E::C, true, None); 5], &anell(5)).unwrap_err(),
+            Problema::Aromaticitat
+        );
+
+        let mut metil: Vec<AtomLlegit> = vec![(E::C, false, None)];
+        metil.extend([(E::F, false, None); 5]);
+        let enllacos: Vec<_> = (1..6).map(|i| (0, i, Some(1))).collect();
+        assert_eq!(
+            super::completa(&metil, &enllacos).unwrap_err(),
+            Problema::Valencia(E::C)
+        );
+        assert_eq!(
+            super::completa(&[(E::C, false, Some(0))], &[]).unwrap_err(),
+            Problema::MigFer(E::C)
+        );
+        assert_eq!(
+            super::completa(
+                &[(E::C, false, None); 2],
+                &[(0, 1, Some(1)), (1, 0, Some(1))]
+            )
+            .unwrap_err(),
+            Problema::EnllacRepetit
+        );
+    }
+}
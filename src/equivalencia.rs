@@ -0,0 +1,220 @@
+//! Si dos dibuixos són el mateix compost. Es comparen àtom per àtom, perquè no importi quins
+//! ports s'han fet servir, si s'ha dibuixat girat ni si s'ha fet amb grups o amb àtoms sols
+use crate::{
+    Molecule,
+    atoms::{Atoms, desplega},
+};
+
+/// Si les dues molècules són el mateix compost: si hi ha una manera de fer correspondre els
+/// àtoms de l'una amb els de l'altra que conservi els enllaços i el seu ordre. Els anells
+/// aromàtics, també els fusionats com el naftalè, són iguals siguin on siguin els dobles
+/// enllaços
+pub fn son_equivalents(a: &Molecule, b: &Molecule) -> bool {
+    let (mut x, _) = desplega(a);
+    let (mut y, _) = desplega(b);
+    let n = x.elements.len();
+    if n != y.elements.len() {
+        return false;
+    }
+    let mut aromatic = x.aromatitza();
+    aromatic.extend(y.aromatitza());
+
+    // Les classes es fan amb totes dues juntes, perquè siguin les mateixes a les dues
+    let mut unio = x.clone();
+    for i in 0..n {
+        unio.afegeix(y.elements[i], y.h[i]);
+    }
+    for i in 0..n {
+        for &(j, o) in &y.adj[i] {
+            if i < j {
+                unio.enllaca(n + i, n + j, o);
+            }
+        }
+    }
+    let classe = unio.classes_equivalents(&aromatic);
+    let (mut de_x, mut de_y) = (classe[..n].to_vec(), classe[n..].to_vec());
+    de_x.sort();
+    de_y.sort();
+    if de_x != de_y {
+        return false;
+    }
+
+    let ordre = ordre_de_visita(&x);
+    let mut cap_a = vec![None; n];
+    let mut usat = vec![false; n];
+    let c = Correspondencia {
+        x: &x,
+        y: &y,
+        classe_x: &classe[..n],
+        classe_y: &classe[n..],
+    };
+    c.busca(&ordre, &mut cap_a, &mut usat)
+}
+
+/// Els àtoms en l'ordre en què es recorren, cada un al costat dels ja visitats, perquè els
+/// enllaços descartin les correspondències dolentes com més aviat millor
+fn ordre_de_visita(x: &Atoms) -> Vec<usize> {
+    let n = x.elements.len();
+    let mut vist = vec![false; n];
+    let mut ordre = vec![];
+    for inici in 0..n {
+        if vist[inici] {
+            continue;
+        }
+        vist[inici] = true;
+        let mut cua = std::collections::VecDeque::from([inici]);
+        while let Some(i) = cua.pop_front() {
+            ordre.push(i);
+            for &(v, _) in &x.adj[i] {
+                if !vist[v] {
+                    vist[v] = true;
+                    cua.push_back(v);
+                }
+            }
+        }
+    }
+    ordre
+}
+
+struct Correspondencia<'a> {
+    x: &'a Atoms,
+    y: &'a Atoms,
+    classe_x: &'a [usize],
+    classe_y: &'a [usize],
+}
+
+impl Correspondencia<'_> {
+    /// Fa correspondre els àtoms d'`ordre` que falten, tornant enrere quan no es pot
+    fn busca(&self, ordre: &[usize], cap_a: &mut [Option<usize>], usat: &mut [bool]) -> bool {
+        let Some((&i, resta)) = ordre.split_first() else {
+            return true;
+        };
+        for j in 0..self.y.elements.len() {
+            if usat[j] || self.classe_x[i] != self.classe_y[j] || !self.encaixa(i, j, cap_a) {
+                continue;
+            }
+            cap_a[i] = Some(j);
+            usat[j] = true;
+            if self.busca(resta, cap_a, usat) {
+                return true;
+            }
+            cap_a[i] = None;
+            usat[j] = false;
+        }
+        false
+    }
+
+    /// Si `i` pot anar a `j`: els veïns d'`i` que ja tenen parella han de ser veïns de `j`,
+    /// amb el mateix ordre d'enllaç, i no n'hi pot haver d'altres
+    fn encaixa(&self, i: usize, j: usize, cap_a: &[Option<usize>]) -> bool {
+        let mut amb_parella = 0;
+        for &(v, o) in &self.x.adj[i] {
+            if let Some(w) = cap_a[v] {
+                if self.y.ordre(j, w) != o {
+                    return false;
+                }
+                amb_parella += 1;
+            }
+        }
+        let de_j = self.y.adj[j]
+            .iter()
+            .filter(|(w, _)| cap_a.contains(&Some(*w)))
+            .count();
+        amb_parella == de_j
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UiRadical::*;
+
+    fn cadena(n: usize) -> Vec<(usize, usize, usize)> {
+        (1..n).map(|i| (i - 1, i, 1)).collect()
+    }
+
+    #[test]
+    fn el_mateix_dibuixat_diferent() {
+        let propa = Molecule::de(&[C, C, C], &cadena(3));
+        let mut girat = Molecule::default();
+        let c: Vec<usize> = (0..3).map(|_| girat.add(C)).collect();
+        girat.bond((c[2], 3), (c[0], 2), 1);
+        girat.bond((c[0], 0), (c[1], 1), 1);
+        assert!(son_equivalents(&propa, &girat));
+
+        // L'àcid acètic amb el bloc de l'àcid i amb el de l'èster sense res a l'oxigen
+        let acetic = Molecule::de(&[C, Carboxil], &[(0, 1, 1)]);
+        assert!(son_equivalents(
+            &acetic,
+            &Molecule::de(&[C, Ester], &[(0, 1, 1)])
+        ));
+        // I àtom per àtom
+        let atoms = Molecule::de(&[C, C, O, O], &[(0, 1, 1), (1, 2, 2), (1, 3, 1)]);
+        assert!(son_equivalents(&acetic, &atoms));
+
+        let kekule = Molecule::de(
+            &[C; 6],
+            &[
+                (0, 1, 2),
+                (1, 2, 1),
+                (2, 3, 2),
+                (3, 4, 1),
+                (4, 5, 2),
+                (5, 0, 1),
+            ],
+        );
+        assert!(son_equivalents(&kekule, &Molecule::de(&[Benze], &[])));
+        // Els naftalens amb els dobles enllaços a llocs diferents: a l'un els dos anells els
+        // tenen alternats i a l'altre només un
+        let naftale = |dobles: [(usize, usize); 5]| {
+            let enllacos: Vec<_> = [
+                (0, 1),
+                (1, 2),
+                (2, 3),
+                (3, 4),
+                (4, 5),
+                (5, 0),
+                (4, 6),
+                (6, 7),
+                (7, 8),
+                (8, 9),
+                (9, 5),
+            ]
+            .into_iter()
+            .map(|(a, b)| (a, b, if dobles.contains(&(a, b)) { 2 } else { 1 }))
+            .collect();
+            Molecule::de(&[C; 10], &enllacos)
+        };
+        assert!(son_equivalents(
+            &naftale([(0, 1), (2, 3), (4, 5), (6, 7), (8, 9)]),
+            &naftale([(0, 1), (2, 3), (4, 6), (7, 8), (9, 5)])
+        ));
+    }
+
+    #[test]
+    fn compostos_diferents() {
+        let buta = Molecule::de(&[C; 4], &cadena(4));
+        let isobuta = Molecule::de(&[C; 4], &[(0, 1, 1), (0, 2, 1), (0, 3, 1)]);
+        assert!(!son_equivalents(&buta, &isobuta));
+
+        // L'etanoat de metil i el metanoat d'etil
+        let etanoat = Molecule::de(&[C, Ester, C], &cadena(3));
+        let mut metanoat = Molecule::de(&[C, C], &[(0, 1, 1)]);
+        let ester = metanoat.add(Ester);
+        metanoat.bond((ester, 1), (1, 1), 1);
+        assert!(!son_equivalents(&etanoat, &metanoat));
+
+        let ciclohexa = |m: &mut Molecule| {
+            let c: Vec<usize> = (0..6).map(|_| m.add(C)).collect();
+            for k in 0..6 {
+                m.bond((c[k], 0), (c[(k + 1) % 6], 1), 1);
+            }
+        };
+        let mut dos = Molecule::default();
+        ciclohexa(&mut dos);
+        ciclohexa(&mut dos);
+        let mut ciclododeca = Molecule::de(&[C; 12], &cadena(12));
+        ciclododeca.bond((11, 1), (0, 1), 1);
+        assert!(!son_equivalents(&dos, &ciclododeca));
+    }
+}
@@ -0,0 +1,140 @@
+//! La fórmula molecular i la massa molar de les molècules. Els enllaços buits són hidrògens
+use crate::{Molecule, NamingError};
+use std::collections::BTreeMap;
+
+/// (símbol, massa atòmica estàndard en g/mol)
+const MASSES: [(&str, f64); 9] = [
+    ("C", 12.011),
+    ("H", 1.008),
+    ("N", 14.007),
+    ("O", 15.999),
+    ("F", 18.998),
+    ("Cl", 35.45),
+    ("Br", 79.904),
+    ("S", 32.06),
+    ("I", 126.90),
+];
+
+/// Quants àtoms de cada element té una molècula. `Display` en dona la fórmula en l'ordre de
+/// Hill: primer el C, després l'H i la resta per ordre alfabètic ("C3H8O"); sense carboni, tots
+/// per ordre alfabètic ("BrH")
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Formula {
+    pub atoms: BTreeMap<&'static str, usize>,
+}
+
+impl Formula {
+    /// La massa molar, en g/mol
+    pub fn molar_mass(&self) -> f64 {
+        self.atoms
+            .iter()
+            .map(|(simbol, n)| {
+                let (_, massa) = MASSES
+                    .iter()
+                    .find(|(s, _)| s == simbol)
+                    .expect("every radical's atoms have a mass");
+                massa * *n as f64
+            })
+            .sum()
+    }
+}
+
+impl std::fmt::Display for Formula {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let primers: &[&str] = match self.atoms.contains_key("C") {
+            true => &["C", "H"],
+            false => &[],
+        };
+        let resta = self.atoms.keys().filter(|s| !primers.contains(s));
+        for simbol in primers.iter().chain(resta) {
+            match self.atoms.get(simbol) {
+                Some(1) => write!(f, "{simbol}")?,
+                Some(n) => write!(f, "{simbol}{n}")?,
+                None => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// La fórmula d'una molècula, o de totes les que hi hagi juntes. No n'hi ha si algun grup fa
+/// més enllaços dels que pot o algun enllaç és de més de tres
+pub fn formula_molecula(molecula: &Molecule) -> Result<Formula, NamingError> {
+    if let Some(problema) = molecula.valence_problem() {
+        return Err(NamingError::InvalidValence(problema));
+    }
+    let mut atoms = BTreeMap::new();
+    for (grup, fets) in molecula.groups.iter().zip(molecula.bonds_per_group()) {
+        for &(simbol, n) in grup.atoms() {
+            *atoms.entry(simbol).or_default() += n;
+        }
+        // Els enllaços buits se consideren H
+        let buits = (grup.valencia() as usize).saturating_sub(fets);
+        if buits > 0 {
+            *atoms.entry("H").or_default() += buits;
+        }
+    }
+    Ok(Formula { atoms })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UiRadical::{self, *};
+
+    #[test]
+    fn ordre_de_hill() {
+        let butanol = Molecule::de(
+            &[C, C, C, C, Alcohol],
+            &[(0, 1, 1), (1, 2, 1), (2, 3, 1), (1, 4, 1)],
+        );
+        let f = formula_molecula(&butanol).unwrap();
+        assert_eq!(f.to_string(), "C4H10O");
+        assert!((f.molar_mass() - 74.123).abs() < 0.01);
+
+        let cloroform = Molecule::de(&[C, Cl, Cl, Cl], &[(0, 1, 1), (0, 2, 1), (0, 3, 1)]);
+        assert_eq!(formula_molecula(&cloroform).unwrap().to_string(), "CHCl3");
+        assert_eq!(
+            formula_molecula(&Molecule::de(&[Benze], &[]))
+                .unwrap()
+                .to_string(),
+            "C6H6"
+        );
+        // Sense carboni, per ordre alfabètic
+        assert_eq!(
+            formula_molecula(&Molecule::de(&[Br], &[]))
+                .unwrap()
+                .to_string(),
+            "BrH"
+        );
+        assert_eq!(
+            formula_molecula(&Molecule::de(&[Amina], &[]))
+                .unwrap()
+                .to_string(),
+            "H3N"
+        );
+    }
+
+    #[test]
+    fn valencies() {
+        let error = |grups: &[UiRadical], enllacos: &[(usize, usize, usize)]| {
+            formula_molecula(&Molecule::de(grups, enllacos)).unwrap_err()
+        };
+        // Un C amb cinc enllaços, un enllaç quàdruple i un H entre dos C
+        assert_eq!(
+            error(
+                &[C; 6],
+                &[(0, 1, 1), (0, 2, 1), (0, 3, 1), (0, 4, 1), (0, 5, 1)]
+            ),
+            NamingError::InvalidValence("un C amb 5 enllaços".to_string())
+        );
+        assert_eq!(
+            error(&[C, C], &[(0, 1, 4)]),
+            NamingError::InvalidValence("un enllaç d'ordre 4 entre C i C".to_string())
+        );
+        assert_eq!(
+            error(&[C, H, C], &[(0, 1, 1), (1, 2, 1)]),
+            NamingError::InvalidValence("un H amb 2 enllaços".to_string())
+        );
+    }
+}
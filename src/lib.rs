@@ -1,4 +1,5 @@
-//! Anomena compostos segons la nomenclatura del 1993 o, si hi escau, amb el seu nom propi
+//! Anomena compostos segons la nomenclatura de la IUPAC (la del 1993, per defecte, o la del
+//! 1979 o la del 2013) o, si hi escau, amb el seu nom propi
 //!
 //! Taula de prioritats
 //! | Grup            | Simbòlic | Preferent   | Subsitutent     |
@@ -12,165 +13,58 @@
 //! | Alcohol/Fenol   | -OH      | -ol         | hidroxi         |
 //! | Amina           | -NH2     | -amina      | amino           |
 //! | Èters           | R-O-R'   | (èter)      | ($R)oxi         |
-//! | Halògens        | Cl,Br,F  | NaN         | ($R)            |
+//! | Halògens        | F,Cl,Br,I| NaN         | ($R)            |
+//!
+//! El nucli (`Molecule`, `anomena_molecula`, `parse_name`, `formula_molecula`, `smiles_molecula`,
+//! `parse_smiles`, `molfile_molecula`, `parse_molfile`, `son_equivalents`) no depèn de raylib. La
+//! interfície gràfica és darrere de la feature `gui`, que és la per defecte
 
 mod anomena;
+mod atoms;
+mod equivalencia;
+mod formula;
+mod molecule;
+mod molfile;
+mod smiles;
+#[cfg(feature = "gui")]
+mod ui;
 pub use anomena::*;
-
-use raylib::prelude::*;
-use std::collections::HashMap;
-use std::rc::Rc;
-
-pub type Id = u128;
-pub const LINK_MARGIN_BETWEEN_RADICAL: f32 = 10.0;
-pub const LINK_LINE_THICKNESS: f32 = 3.0;
-
-pub struct UiState {
-    pub uiblocks: Vec<UiBlock>,
-    pub held: Option<Held>,
-    pub is_help_up: bool,
-    /// for undoing (DO NOT PUSH TO MANUALLY, USE `push_to_undo`)
-    pub undo_list: Vec<UiAction>,
-    /// for unundoing (DO NOT PUSH TO MANUALLY, USE `redo_last`)
-    pub redo_list: Vec<UiAction>,
-    /// (width, height)
-    pub window_dims: (i32, i32),
-    /// Text that shows the name of the molecule
-    pub naming_text: Option<String>,
-}
-
-impl UiState {
-    pub fn push_to_undo(&mut self, a: UiAction) {
-        self.undo_list.push(a);
-        self.redo_list.clear();
-    }
-}
-
-/// A building block of the UI; a node in the network
-#[derive(Debug, Clone)]
-pub struct UiBlock {
-    pub pos: Vector2,
-    pub radical: UiRadical,
-    pub font: Rc<Font>,
-    pub links: Vec<Id>,
-    pub id: Id,
-}
-
-impl UiBlock {
-    pub const PAD_V: f32 = 15.0;
-    pub const PAD_H: f32 = 20.0;
-    pub const LINK_CIRCLE_RADIUS: f32 = 7.0;
-    pub const LINK_PAD: f32 = 10.0 + Self::LINK_CIRCLE_RADIUS;
-    pub const FONT_SIZE: i32 = 30;
-    pub const SPACING: f32 = 0.5; // I don't know what this does but raylib asks for it
-    pub const ROUNDNESS: f32 = 0.75;
-    pub const SEGMENTS: i32 = 20;
-    pub const LINE_THICKNESS: f32 = 4.0;
-
-    pub fn dims(&self) -> Vector2 {
-        let text = self.radical.to_string();
-        self.font
-            .measure_text(&text, Self::FONT_SIZE as f32, Self::SPACING)
-    }
-    pub fn center(&self) -> Vector2 {
-        let (width, height) = (self.dims().x, self.dims().y);
-        let x = self.pos.x - Self::PAD_H + f32::midpoint(Self::PAD_V * 2.0, width);
-        let y = self.pos.y - Self::PAD_V + f32::midpoint(Self::PAD_V * 2.0, height);
-        Vector2 { x, y }
-    }
-
-    /// returns [Up Left Down Right]
-    pub fn general_link_positions(&self) -> [Vector2; 4] {
-        let (width, height) = (self.dims().x, self.dims().y);
-        let Vector2 {
-            x: mid_width,
-            y: mid_height,
-        } = self.center();
-
-        let left = Vector2 {
-            x: self.pos.x - Self::PAD_H - Self::LINK_PAD,
-            y: mid_height,
-        };
-        let right = Vector2 {
-            x: self.pos.x + Self::PAD_H + Self::LINK_PAD + width,
-            y: mid_height,
-        };
-        let up = Vector2 {
-            x: mid_width,
-            y: self.pos.y - Self::PAD_V - Self::LINK_PAD,
-        };
-        let down = Vector2 {
-            x: mid_width,
-            y: self.pos.y + Self::PAD_V + Self::LINK_PAD + height,
-        };
-
-        [up, left, down, right]
-    }
-    pub fn link_positions(&self) -> Vec<Vector2> {
-        let a_poss = self.general_link_positions();
-        let avail = self
-            .radical
-            .valencia()
-            .wrapping_sub(self.links.len() as u32);
-        let l = match self.radical.valencia() {
-            1 => vec![a_poss[3]],
-            2 => vec![a_poss[1], a_poss[3]],
-            3 => vec![a_poss[0], a_poss[1], a_poss[3]],
-            4 => a_poss.to_vec(),
-            l => {
-                eprintln!("ERROR: molecula has {l} valencies, que no és ni 1 ni 2 ni 4");
-                a_poss.to_vec()
-            }
-        };
-        l.into_iter().take(avail as usize).collect()
-    }
-
-    pub fn count_links(blocks: &[UiBlock]) -> HashMap<(Id, Id), usize> {
-        let mut links: HashMap<(Id, Id), usize> = HashMap::new();
-        for block in blocks {
-            for l_id in &block.links {
-                let (a, b) = (block.id.min(*l_id), block.id.max(*l_id));
-                links.entry((a, b)).and_modify(|c| *c += 1).or_insert(1);
-            }
-        }
-        for m in links.values_mut() {
-            *m /= 2;
-        } // don't double count
-        links
-    }
-}
-
-#[derive(Debug, Clone)]
-pub enum Held {
-    /// `.0` is of the form Vec<(id, from)>
-    // /// `origin` is where the mouse was originally when the holding down was initiated
-    Radicals(Vec<(Id, Vector2)>),
-    Link {
-        radical: Id,
-        from: Vector2,
-    },
-    RectangleCreation {
-        from: Vector2,
-    },
-}
+pub use atoms::agrupa_molecula;
+pub use equivalencia::*;
+pub use formula::*;
+pub use molecule::*;
+pub use molfile::*;
+pub use smiles::*;
+#[cfg(feature = "gui")]
+pub use ui::*;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum UiRadical {
     F,
     Cl,
     Br,
+    I,
     C,
     Amina,
     Eter,
-    Fenol,
+    /// Un anell de benzè. Cada port és un dels sis carbonis, en ordre
+    Benze,
     Alcohol,
     Cetona,
     Aldehid,
     Nitril,
     Amida,
+    /// -COO-. No és simètric: el port 0 és el del carboni (l'acil, a l'esquerra) i el port 1 el
+    /// de l'oxigen (l'alquil, a la dreta)
     Ester,
     Carboxil,
     //Amonia,
+    /// Els àtoms sols, per dibuixar àtom a àtom. El nomenador no els veu: abans es pleguen en
+    /// els grups que formen (`agrupa_molecula`)
+    H,
+    N,
+    O,
+    S,
 }
 
 impl UiRadical {
@@ -178,9 +72,12 @@ impl UiRadical {
         use UiRadical as R;
         match self {
             R::C => 4,
-            R::F | R::Cl | R::Br => 1,
+            R::F | R::Cl | R::Br | R::I => 1,
+            R::H => 1,
+            R::N => 3,
+            R::O | R::S => 2,
             R::Amina => 1,
-            R::Fenol => 1,
+            R::Benze => 6,
             R::Alcohol => 1,
             R::Cetona => 2,
             R::Aldehid => 1,
@@ -191,31 +88,56 @@ impl UiRadical {
             R::Eter => 2,
         }
     }
+    /// Labels for the ports of radicals whose ports are not interchangeable
+    pub fn port_labels(&self) -> Option<&'static [&'static str]> {
+        match self {
+            Self::Ester => Some(&["C", "O"]),
+            _ => None,
+        }
+    }
+    /// Si és un dels àtoms sols, que s'han de plegar en grups abans d'anomenar
+    pub fn is_bare_atom(&self) -> bool {
+        matches!(self, Self::H | Self::N | Self::O | Self::S)
+    }
     pub fn contains_carbon(&self) -> bool {
         use UiRadical as R;
         match self {
             R::C
-            | R::Fenol
+            | R::Benze
             | R::Amida
             | R::Ester
             | R::Carboxil
             | R::Cetona
             | R::Aldehid
             | R::Nitril => true,
-            R::F | R::Cl | R::Br | R::Amina | R::Alcohol | R::Eter => false,
+            R::F
+            | R::Cl
+            | R::Br
+            | R::I
+            | R::Amina
+            | R::Alcohol
+            | R::Eter
+            | R::H
+            | R::N
+            | R::O
+            | R::S => false,
         }
     }
     pub fn contains_nitrogen(&self) -> bool {
         use UiRadical as R;
         match self {
-            R::Amina | R::Amida | R::Nitril => true,
+            R::Amina | R::Amida | R::Nitril | R::N => true,
             R::F
             | R::Cl
             | R::Br
+            | R::I
+            | R::H
+            | R::O
+            | R::S
             | R::Alcohol
             | R::Eter
             | R::C
-            | R::Fenol
+            | R::Benze
             | R::Ester
             | R::Carboxil
             | R::Cetona
@@ -225,10 +147,45 @@ impl UiRadical {
     pub fn contains_oxygen(&self) -> bool {
         use UiRadical as R;
         match self {
-            R::Carboxil | R::Ester | R::Cetona | R::Aldehid | R::Alcohol | R::Fenol | R::Eter => {
-                true
-            }
-            R::Amina | R::Amida | R::Nitril | R::F | R::Cl | R::Br | R::C => false,
+            R::Carboxil | R::Ester | R::Cetona | R::Aldehid | R::Alcohol | R::Eter | R::O => true,
+            R::Amina
+            | R::Amida
+            | R::Nitril
+            | R::F
+            | R::Cl
+            | R::Br
+            | R::I
+            | R::C
+            | R::Benze
+            | R::H
+            | R::N
+            | R::S => false,
+        }
+    }
+    /// The atoms the radical stands for, as (symbol, count). The hydrogens of its empty links
+    /// aren't here, only the ones it always has
+    pub fn atoms(&self) -> &'static [(&'static str, usize)] {
+        use UiRadical as R;
+        match self {
+            R::C => &[("C", 1)],
+            R::F => &[("F", 1)],
+            R::Cl => &[("Cl", 1)],
+            R::Br => &[("Br", 1)],
+            R::I => &[("I", 1)],
+            R::H => &[("H", 1)],
+            R::N => &[("N", 1)],
+            R::O => &[("O", 1)],
+            R::S => &[("S", 1)],
+            R::Amina => &[("N", 1), ("H", 2)],
+            R::Eter => &[("O", 1)],
+            R::Benze => &[("C", 6)],
+            R::Alcohol => &[("O", 1), ("H", 1)],
+            R::Cetona => &[("C", 1), ("O", 1)],
+            R::Aldehid => &[("C", 1), ("H", 1), ("O", 1)],
+            R::Nitril => &[("C", 1), ("N", 1)],
+            R::Amida => &[("C", 1), ("O", 1), ("N", 1), ("H", 2)],
+            R::Ester => &[("C", 1), ("O", 2)],
+            R::Carboxil => &[("C", 1), ("O", 2), ("H", 1)],
         }
     }
 }
@@ -239,8 +196,13 @@ impl std::fmt::Display for UiRadical {
             Self::F => "F",
             Self::Cl => "Cl",
             Self::Br => "Br",
+            Self::I => "I",
+            Self::H => "H",
+            Self::N => "N",
+            Self::O => "O",
+            Self::S => "S",
             Self::Amina => "NH2",
-            Self::Fenol => "Fenol",
+            Self::Benze => "Benze",
             Self::Alcohol => "OH",
             Self::Cetona => "CO",
             Self::Aldehid => "CHO",
@@ -253,251 +215,3 @@ impl std::fmt::Display for UiRadical {
         write!(f, "{s}")
     }
 }
-
-#[derive(Debug, Clone)]
-pub enum UiAction {
-    AddRadical(UiBlock),
-    DeleteRadical(UiBlock),
-    /// (id, from, to)
-    MoveRadicals(Vec<(Id, Vector2, Vector2)>),
-    AddLink(Id, Id),
-    DeleteLink(Id, Id),
-}
-
-impl UiAction {
-    pub fn opposite(&self) -> Self {
-        match self {
-            Self::AddRadical(what) => Self::DeleteRadical(what.clone()),
-            Self::DeleteRadical(what) => Self::AddRadical(what.clone()),
-            Self::MoveRadicals(data) => {
-                Self::MoveRadicals(data.iter().map(|(i, f, t)| (*i, *t, *f)).collect())
-            }
-            Self::AddLink(a, b) => Self::DeleteLink(*b, *a),
-            Self::DeleteLink(a, b) => Self::AddLink(*b, *a),
-        }
-    }
-}
-
-// the `raylib` library forgot to put this function in raylib::core::collision -_-
-pub fn is_point_in_rect(p: Vector2, rect: Rectangle) -> bool {
-    (p.x >= rect.x && p.x <= rect.x + rect.width) && (p.y >= rect.y && p.y <= rect.y + rect.height)
-}
-
-pub fn is_point_in_block(p: Vector2, b: &UiBlock) -> bool {
-    use UiBlock as B;
-    is_point_in_rect(
-        p,
-        Rectangle {
-            x: b.pos.x - B::PAD_H,
-            y: b.pos.y - B::PAD_V,
-            width: b.dims().x + 2.0 * B::PAD_H,
-            height: b.dims().y + 2.0 * B::PAD_H,
-        },
-    )
-}
-
-pub fn get_block_under_point(bs: &[UiBlock], cursor: Vector2) -> Option<&UiBlock> {
-    bs.iter().find(|b| is_point_in_block(cursor, b))
-}
-
-pub fn remove_hanging_links(blocks: &mut Vec<UiBlock>, del_id: Id) {
-    for b in blocks {
-        b.links.retain(|&l| l != del_id)
-    }
-}
-
-pub fn link_node_at_point(
-    blocks: &[UiBlock],
-    curr_mouse_pos: Vector2,
-    threshold: f32,
-) -> Option<(Id, Vector2)> {
-    for b in blocks {
-        for c in b.link_positions() {
-            if check_collision_point_circle(
-                curr_mouse_pos,
-                c,
-                UiBlock::LINK_CIRCLE_RADIUS + threshold,
-            ) {
-                return Some((b.id, c));
-            }
-        }
-    }
-    None
-}
-
-pub fn get_block_unchecked(blocks: &[UiBlock], id: Id) -> &UiBlock {
-    blocks
-        .iter()
-        .find(|b| b.id == id)
-        .unwrap_or_else(|| panic!("Block unexpectedly disappeared: {id}"))
-}
-pub fn get_two_blocks_unchecked_mut(
-    blocks: &mut [UiBlock],
-    id_a: Id,
-    id_b: Id,
-) -> (&mut UiBlock, &mut UiBlock) {
-    let a = blocks
-        .iter()
-        .position(|bl| bl.id == id_a)
-        .unwrap_or_else(|| panic!("Block unexpectedly disappeared: {id_a}"));
-    let b = blocks
-        .iter()
-        .position(|bl| bl.id == id_b)
-        .unwrap_or_else(|| panic!("Block unexpectedly disappeared: {id_b}"));
-    let [r1, r2] = blocks
-        .get_disjoint_mut([a, b])
-        .expect("Invalid indices to unchecked function");
-    (r1, r2)
-}
-pub fn get_block_unchecked_mut(blocks: &mut [UiBlock], id: Id) -> &mut UiBlock {
-    blocks
-        .iter_mut()
-        .find(|b| b.id == id)
-        .unwrap_or_else(|| panic!("Block unexpectedly disappeared: {id}"))
-}
-
-pub fn get_points_for_link(b: &UiBlock, bp: &UiBlock) -> [Vector2; 2] {
-    let Vector2 { x: b_w, y: b_h } = b.dims();
-    let Vector2 { x: bp_w, y: bp_h } = bp.dims();
-    let b_c = b.center();
-    let bp_c = bp.center();
-    let m = LINK_MARGIN_BETWEEN_RADICAL;
-
-    //let angle = ;
-
-    // What follows is incredibly scuffed
-    // We divide the line into three cases (per rectangle): closest to short, closest
-    // to long and closest to corner.
-
-    // Assuming we're 'close' to top right corner
-    // A is the 'bottom' point of the rounding circle
-    // B is the 'left' point of the rounding circle
-    // i.e. they're both the same distance away from the corner (m)
-    //
-    // +--------B--+
-    // |           |
-    // |           A
-    // |           |
-    // |           |
-    // +-----------+
-    let a = Vector2 {
-        x: b_c.x + b_w / 2.0,
-        y: b_c.y + b_h / 2.0 - m,
-    };
-    let b = Vector2 {
-        x: b_c.x + b_w / 2.0 - m,
-        y: b_c.y + b_h / 2.0,
-    };
-    let alpha = (a.y / a.x).atan();
-    let beta = (b.y / b.x).atan();
-
-    // TODO: replace this with actual logic
-    [b_c, bp_c]
-}
-
-pub fn undo_last(st: &mut UiState) {
-    let Some(action) = st.undo_list.pop() else {
-        return;
-    };
-    eprintln!("dbg: Undoing last action: {action:x?}");
-
-    undo_action(st, action.clone());
-    st.redo_list.push(action.opposite());
-}
-pub fn redo_last(st: &mut UiState) {
-    let Some(action) = st.redo_list.pop() else {
-        return;
-    };
-    eprintln!("dbg: REdoing last action: {action:x?}");
-
-    undo_action(st, action.clone());
-    st.undo_list.push(action.opposite());
-}
-
-fn undo_action(st: &mut UiState, action: UiAction) {
-    match action {
-        UiAction::AddRadical(what) => {
-            if let Some(index) = st.uiblocks.iter().position(|b| b.id == what.id) {
-                st.uiblocks.remove(index);
-                remove_hanging_links(&mut st.uiblocks, what.id);
-            }
-        }
-        UiAction::MoveRadicals(data) => {
-            for (id, from, to) in data {
-                if let Some(block) = st.uiblocks.iter_mut().find(|b| b.id == id) {
-                    block.pos = from;
-                }
-            }
-        }
-        UiAction::DeleteRadical(what) => st.uiblocks.push(what),
-        UiAction::AddLink(a_id, b_id) => {
-            let (a, b) = get_two_blocks_unchecked_mut(&mut st.uiblocks, a_id, b_id);
-            if let Some(i) = a.links.iter().position(|&l| l == b_id) {
-                a.links.remove(i);
-            }
-            if let Some(i) = b.links.iter().position(|&l| l == a_id) {
-                b.links.remove(i);
-            }
-        }
-        UiAction::DeleteLink(a_id, b_id) => {
-            let (a, b) = get_two_blocks_unchecked_mut(&mut st.uiblocks, a_id, b_id);
-            a.links.push(b_id);
-            b.links.push(a_id);
-        }
-    }
-}
-
-pub fn delete_under_cursor(st: &mut UiState, curr_mouse_pos: Vector2) {
-    if let Some(index) = st
-        .uiblocks
-        .iter()
-        .position(|b| is_point_in_block(curr_mouse_pos, b))
-    {
-        let what = st.uiblocks.remove(index);
-        remove_hanging_links(&mut st.uiblocks, what.id);
-        st.push_to_undo(UiAction::DeleteRadical(what));
-    } else {
-        // remove link if it exists
-        for ((a_id, b_id), m) in UiBlock::count_links(&st.uiblocks) {
-            let a = get_block_unchecked(&st.uiblocks, a_id);
-            let b = get_block_unchecked(&st.uiblocks, b_id);
-            if cursor_on_link(curr_mouse_pos, a, b, m) {
-                remove_link(&mut st.uiblocks, a_id, b_id);
-                st.push_to_undo(UiAction::DeleteLink(a_id, b_id));
-            }
-        }
-    }
-}
-
-/// Does nothing if the links don't exist
-pub fn remove_link(blocks: &mut [UiBlock], a_id: Id, b_id: Id) {
-    let (a, b) = get_two_blocks_unchecked_mut(blocks, a_id, b_id);
-    if let (Some(i_a), Some(i_b)) = (
-        a.links.iter().position(|&l| l == b_id),
-        b.links.iter().position(|&l| l == a_id),
-    ) {
-        a.links.remove(i_a);
-        b.links.remove(i_b);
-    } else {
-        eprintln!("Tried to remove a link between {a_id:x} and {b_id:x}, but there was none");
-    }
-}
-
-pub fn cursor_on_link(mouse: Vector2, a: &UiBlock, b: &UiBlock, multiplicitat: usize) -> bool {
-    let [c1, c2] = get_points_for_link(a, b);
-    // Assuming the line flows from c1->c2
-
-    let slope = (c2.y - c1.y) / (c2.x - c1.x);
-    let max_dist = (LINK_LINE_THICKNESS * 2.0 - 1.0) * 1.5; // give 50% margin
-    let dist = {
-        // line<->point distance
-        let (a, b, c) = (slope, -1.0, c1.y - slope * c1.x);
-        ((a * mouse.x + b * mouse.y + c) / (a * a + b * b).sqrt()).abs()
-    };
-    let is_in_between = c1.x.min(c2.x) <= mouse.x
-        && mouse.x <= c1.x.max(c2.x)
-        && c1.y.min(c2.y) <= mouse.y
-        && mouse.y <= c1.y.max(c2.y);
-
-    dist <= max_dist && is_in_between
-}
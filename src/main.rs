@@ -6,6 +6,8 @@ use std::rc::Rc;
 const APL387_BYTES: &[u8] = include_bytes!("../APL387.ttf");
 const HELP_TEXT_FONTSIZE: i32 = 25;
 const LINK_CIRCLE_CLICKING_THRESHOLD: f32 = 20.0;
+/// For the ports of radicals that have labelled ports (see `UiRadical::port_labels`)
+const PORT_COLORS: [Color; 2] = [Color::DARKGRAY, Color::RED];
 const KEYMAP: [(KeyboardKey, UiRadical); 15] = [
     (KeyboardKey::KEY_C, UiRadical::C),
     (KeyboardKey::KEY_Q, UiRadical::C),
@@ -16,13 +18,26 @@ const KEYMAP: [(KeyboardKey, UiRadical); 15] = [
     (KeyboardKey::KEY_Y, UiRadical::Aldehid),
     (KeyboardKey::KEY_U, UiRadical::Cetona),
     (KeyboardKey::KEY_I, UiRadical::Alcohol),
-    (KeyboardKey::KEY_O, UiRadical::Fenol),
+    (KeyboardKey::KEY_O, UiRadical::Benze),
     (KeyboardKey::KEY_P, UiRadical::Amina),
     (KeyboardKey::KEY_A, UiRadical::Eter),
     (KeyboardKey::KEY_S, UiRadical::Br),
     (KeyboardKey::KEY_D, UiRadical::Cl),
     (KeyboardKey::KEY_F, UiRadical::F),
 ];
+/// The palette after F7: single atoms, which are folded into groups before naming
+const ATOM_KEYMAP: [(KeyboardKey, UiRadical); 10] = [
+    (KeyboardKey::KEY_C, UiRadical::C),
+    (KeyboardKey::KEY_Q, UiRadical::C),
+    (KeyboardKey::KEY_W, UiRadical::H),
+    (KeyboardKey::KEY_E, UiRadical::N),
+    (KeyboardKey::KEY_R, UiRadical::O),
+    (KeyboardKey::KEY_T, UiRadical::S),
+    (KeyboardKey::KEY_Y, UiRadical::F),
+    (KeyboardKey::KEY_U, UiRadical::Cl),
+    (KeyboardKey::KEY_I, UiRadical::Br),
+    (KeyboardKey::KEY_O, UiRadical::I),
+];
 
 // TODO: when hovering over a tag, make its connected graph glow/get highlighted
 // TODO: Maybe, bulk select to move?
@@ -43,9 +58,9 @@ fn main() {
         .expect("Cannot fail, font is loaded in at compile time");
 
     rl.set_target_fps(120);
-    let help_text = {
+    let help_text = |keymap: &[(KeyboardKey, UiRadical)]| {
         let mut t = String::new();
-        for (k, r) in KEYMAP {
+        for (k, r) in keymap {
             write!(
                 t,
                 "{} -> Afegeix {}\n",
@@ -55,6 +70,23 @@ fn main() {
         }
         t.push_str("X -> Elimina\n");
         t.push_str("N -> Anomena mol. sota cursor\n");
+        t.push_str("L -> Posa el nom al costat de cada molecula (o el treu)\n");
+        t.push_str("M -> Explica pas a pas el nom de la mol. sota cursor\n");
+        t.push_str("F1 -> Alterna noms d'eters (metoxieta / etil metil eter)\n");
+        t.push_str("F2 -> Alterna posicions del benze (1,2-diclorobenze / o-diclorobenze)\n");
+        t.push_str("F3 -> Canvia els noms propis (propanona / propanona (acetona) / acetona)\n");
+        t.push_str("F4 -> Canvia l'idioma (catala / castella / angles)\n");
+        t.push_str("F5 -> Alterna noms curts (isopropil / (1-metiletil))\n");
+        t.push_str("F6 -> Canvia l'edicio de la IUPAC (1979 / 1993 / 2013)\n");
+        t.push_str("F7 -> Alterna la paleta (grups / atoms)\n");
+        t.push_str("Enter -> Escriu un nom (o un SMILES) i dibuixa'l sota el cursor\n");
+        t.push_str("J -> Copia el SMILES de la mol. sota cursor\n");
+        t.push_str(
+            "G -> Tria la mol. sota cursor; G sobre una altra diu si son el mateix compost\n",
+        );
+        t.push_str("K -> Desa la mol. sota cursor a molecula.mol (Shift: totes a molecules.sdf)\n");
+        t.push_str("     Si ja hi son, a molecula-2.mol, molecula-3.mol...\n");
+        t.push_str("Arrossega un .mol o un .sdf a la finestra per dibuixar-lo\n");
         t.push_str("Z -> Undo/Desfer\n");
         // TODO: Find out why all non-ascii becomes question marks??? I
         // geniunely don't understand why even draw_text_codepoints doesn't
@@ -62,6 +94,7 @@ fn main() {
         t.push_str("(Els enllaços buits se consideren H)");
         t
     };
+    let help_texts = [help_text(&KEYMAP), help_text(&ATOM_KEYMAP)];
 
     let mut st = UiState {
         uiblocks: vec![],
@@ -71,6 +104,12 @@ fn main() {
         redo_list: vec![],
         window_dims: (rl.get_render_width(), rl.get_render_height()),
         naming_text: None,
+        naming_options: NamingOptions::default(),
+        name_input: None,
+        labels: vec![],
+        formula_panel: None,
+        atom_palette: false,
+        selected: None,
     };
 
     let mut curr_mouse_pos = rl.get_mouse_position();
@@ -106,6 +145,37 @@ fn main() {
             *from *= rescale_factor
         }
 
+        // ===== Handle dropped files =====
+        if rl.is_file_dropped() {
+            // Each molecule of a .sdf goes to the right of the previous one
+            let mut center = curr_mouse_pos;
+            let (mut drawn, mut errors) = (0, vec![]);
+            for path in rl.load_dropped_files().paths() {
+                let structures = match std::fs::read_to_string(path) {
+                    Ok(text) if path.to_lowercase().ends_with(".sdf") => parse_sdf(&text),
+                    Ok(text) => vec![parse_molfile(&text)],
+                    Err(e) => vec![Err(MolfileError::Unsupported(e.to_string()))],
+                };
+                for s in structures {
+                    match s {
+                        Ok(s) => {
+                            let width = st.add_structure(&s, apl387.clone(), center);
+                            center.x += width + 200.0;
+                            drawn += 1;
+                        }
+                        Err(e) => errors.push(e.to_string()),
+                    }
+                }
+            }
+            st.naming_text = Some(match errors.first() {
+                None => format!("{drawn} molecules carregades"),
+                Some(e) => format!(
+                    "{drawn} molecules carregades, {} errors. ERR: {e}",
+                    errors.len()
+                ),
+            });
+        }
+
         // ===== Handle clicking =====
         match (
             &st.held,
@@ -121,10 +191,14 @@ fn main() {
                         st.uiblocks[index].id,
                         st.uiblocks[index].pos,
                     )]));
-                } else if let Some((radical, from)) =
+                } else if let Some((radical, port, from)) =
                     link_node_at_point(&st.uiblocks, curr_mouse_pos, LINK_CIRCLE_CLICKING_THRESHOLD)
                 {
-                    st.held = Some(Held::Link { radical, from });
+                    st.held = Some(Held::Link {
+                        radical,
+                        port,
+                        from,
+                    });
                 } else {
                     st.held = Some(Held::RectangleCreation {
                         from: curr_mouse_pos,
@@ -151,18 +225,18 @@ fn main() {
             (Some(Held::Link { .. }), true) => {}
             (
                 Some(Held::Link {
-                    radical: source_id, ..
+                    radical: source_id,
+                    port: source_port,
+                    ..
                 }),
                 false,
             ) => {
-                if let Some((dest_id, ..)) =
+                if let Some((dest_id, dest_port, _)) =
                     link_node_at_point(&st.uiblocks, curr_mouse_pos, LINK_CIRCLE_CLICKING_THRESHOLD)
                 {
-                    let (source, dest) =
-                        get_two_blocks_unchecked_mut(&mut st.uiblocks, *source_id, dest_id);
-                    source.links.push(dest_id);
-                    dest.links.push(*source_id);
-                    st.push_to_undo(UiAction::AddLink(*source_id, dest_id));
+                    let (source, dest) = ((*source_id, *source_port), (dest_id, dest_port));
+                    add_link(&mut st.uiblocks, source, dest);
+                    st.push_to_undo(UiAction::AddLink(source, dest));
                 }
                 st.held = None;
             }
@@ -182,44 +256,247 @@ fn main() {
         }
 
         // ===== Handle keypresses =====
-        for (_, radical) in KEYMAP.iter().filter(|(k, _)| rl.is_key_pressed(*k)) {
-            let r = 10;
-            let rand_delta = Vector2 {
-                x: rand::random_range(-r..=r) as f32,
-                y: rand::random_range(-r..=r) as f32,
-            };
-            let b = UiBlock {
-                pos: curr_mouse_pos + rand_delta,
-                radical: *radical,
-                font: apl387.clone(),
-                id: rand::random(),
-                links: vec![],
-            };
-            st.push_to_undo(UiAction::AddRadical(b.clone()));
-            st.uiblocks.push(b);
-        }
-        if rl.is_key_pressed(KeyboardKey::KEY_Z) && !rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) {
-            undo_last(&mut st)
-        }
-        if rl.is_key_pressed(KeyboardKey::KEY_Z) && rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) {
-            redo_last(&mut st)
-        }
-        if rl.is_key_pressed(KeyboardKey::KEY_X) {
-            delete_under_cursor(&mut st, curr_mouse_pos)
-        }
-        if rl.is_key_pressed(KeyboardKey::KEY_H) {
-            st.is_help_up = !st.is_help_up
-        }
-        if rl.is_key_pressed(KeyboardKey::KEY_N) {
-            if let Some(b) = get_block_under_point(&st.uiblocks, curr_mouse_pos) {
-                st.naming_text = Some(anomena(&st.uiblocks, b));
+        if let Some(mut input) = st.name_input.take() {
+            // Typing a name: nothing else reacts to the keyboard until Enter
+            while let Some(c) = rl.get_char_pressed() {
+                input.push(c);
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) {
+                input.pop();
+            }
+            if !rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+                st.naming_text = Some(format!("Nom: {input}_"));
+                st.name_input = Some(input);
+            } else if input.trim().is_empty() {
+                st.naming_text = None;
             } else {
-                st.naming_text = Some("No he trobat res sota el cursor :c".to_string());
+                // Si no és un nom, potser és un SMILES. Si tampoc, l'error que es veu és el del nom
+                let llegit = parse_name(&input, &st.naming_options)
+                    .or_else(|e| parse_smiles(&input).map_err(|_| e));
+                match llegit {
+                    Ok(s) => {
+                        st.add_structure(&s, apl387.clone(), curr_mouse_pos);
+                        st.naming_text = None;
+                    }
+                    Err(e) => st.naming_text = Some(format!("ERR: {e}")),
+                }
+            }
+        } else {
+            let keymap: &[_] = if st.atom_palette {
+                &ATOM_KEYMAP
+            } else {
+                &KEYMAP
+            };
+            for (_, radical) in keymap.iter().filter(|(k, _)| rl.is_key_pressed(*k)) {
+                let r = 10;
+                let rand_delta = Vector2 {
+                    x: rand::random_range(-r..=r) as f32,
+                    y: rand::random_range(-r..=r) as f32,
+                };
+                let b = UiBlock {
+                    pos: curr_mouse_pos + rand_delta,
+                    radical: *radical,
+                    font: apl387.clone(),
+                    id: rand::random(),
+                    links: vec![],
+                };
+                st.push_to_undo(UiAction::AddRadical(b.clone()));
+                st.uiblocks.push(b);
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_Z) && !rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT)
+            {
+                undo_last(&mut st)
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_Z) && rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT)
+            {
+                redo_last(&mut st)
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_X) {
+                delete_under_cursor(&mut st, curr_mouse_pos)
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_H) {
+                st.is_help_up = !st.is_help_up
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_N) {
+                if let Some(b) = get_block_under_point(&st.uiblocks, curr_mouse_pos) {
+                    st.naming_text = Some(match anomena(&st.uiblocks, b, &st.naming_options) {
+                        Ok(nom) => nom.to_string(),
+                        Err(e) => format!("ERR: {e}"),
+                    });
+                } else {
+                    st.naming_text = Some("No he trobat res sota el cursor :c".to_string());
+                }
+            } else if rl.is_key_pressed(KeyboardKey::KEY_M) {
+                if let Some(b) = get_block_under_point(&st.uiblocks, curr_mouse_pos) {
+                    st.naming_text = Some(match anomena(&st.uiblocks, b, &st.naming_options) {
+                        Ok(nom) => {
+                            let mut t = nom.to_string();
+                            let passos = nom.systematic.map(|s| s.explanation).unwrap_or_default();
+                            for (i, pas) in passos.iter().enumerate() {
+                                write!(t, "\n{}. {pas}", i + 1).unwrap();
+                            }
+                            t
+                        }
+                        Err(e) => format!("ERR: {e}"),
+                    });
+                } else {
+                    st.naming_text = Some("No he trobat res sota el cursor :c".to_string());
+                }
+            } else if rl.is_key_pressed(KeyboardKey::KEY_K) {
+                // Shift+K saves every molecule, K only the one under the cursor
+                let (name, text) = if rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) {
+                    (
+                        ("molecules", "sdf"),
+                        Some(sdf(&st.uiblocks, &st.naming_options)),
+                    )
+                } else {
+                    let b = get_block_under_point(&st.uiblocks, curr_mouse_pos);
+                    let text = b.map(|b| molfile(&st.uiblocks, b, &st.naming_options));
+                    (("molecula", "mol"), text)
+                };
+                st.naming_text = Some(match text {
+                    Some(Ok(t)) => match save_new(name, &t) {
+                        Ok(path) => format!("Desat a {path}"),
+                        Err(e) => format!("ERR: {e}"),
+                    },
+                    Some(Err(e)) => format!("ERR: {e}"),
+                    None => "No he trobat res sota el cursor :c".to_string(),
+                });
+            } else if rl.is_key_pressed(KeyboardKey::KEY_G) {
+                // The first press picks a molecule and the second compares it with another one
+                let under = get_block_under_point(&st.uiblocks, curr_mouse_pos);
+                let picked = st
+                    .selected
+                    .and_then(|id| st.uiblocks.iter().find(|b| b.id == id));
+                let (selected, text) = match (picked, under) {
+                    (_, None) => (None, "No he trobat res sota el cursor :c".to_string()),
+                    (None, Some(b)) => (
+                        Some(b.id),
+                        "G sobre l'altra molecula per comparar-les".to_string(),
+                    ),
+                    (Some(a), Some(b)) if connected_ids(&st.uiblocks, a).contains(&b.id) => (
+                        Some(a.id),
+                        "Es la mateixa que has triat; G sobre una altra".to_string(),
+                    ),
+                    (Some(a), Some(b)) if equivalents(&st.uiblocks, a, b) => {
+                        (None, "Son el mateix compost".to_string())
+                    }
+                    (Some(a), Some(b)) => {
+                        let (fa, fb) = (formula(&st.uiblocks, a), formula(&st.uiblocks, b));
+                        (
+                            None,
+                            match (fa, fb) {
+                                (Ok(fa), Ok(fb)) if fa == fb => {
+                                    format!("No son el mateix compost: son isomers ({fa})")
+                                }
+                                (Ok(fa), Ok(fb)) => {
+                                    format!("No son el mateix compost ({fa} / {fb})")
+                                }
+                                (Err(e), _) | (_, Err(e)) => e.to_string(),
+                            },
+                        )
+                    }
+                };
+                st.selected = selected;
+                st.naming_text = Some(text);
+            } else if rl.is_key_pressed(KeyboardKey::KEY_J) {
+                if let Some(b) = get_block_under_point(&st.uiblocks, curr_mouse_pos) {
+                    let smiles = smiles(&st.uiblocks, b);
+                    // Sense porta-retalls, almenys es veu
+                    let _ = rl.set_clipboard_text(&smiles);
+                    st.naming_text = Some(format!("SMILES: {smiles}"));
+                } else {
+                    st.naming_text = Some("No he trobat res sota el cursor :c".to_string());
+                }
+            } else if rl.get_key_pressed().is_some()
+                || rl.is_mouse_button_down(MouseButton::MOUSE_BUTTON_LEFT)
+            {
+                st.naming_text = None
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_L) {
+                st.labels = if st.labels.is_empty() {
+                    anomena_totes(&st.uiblocks, &st.naming_options)
+                        .into_iter()
+                        .map(|(ids, nom)| match nom {
+                            Ok(nom) => (ids, nom.to_string()),
+                            Err(e) => (ids, format!("ERR: {e}")),
+                        })
+                        .collect()
+                } else {
+                    vec![]
+                };
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_F1) {
+                let opts = &mut st.naming_options;
+                opts.functional_class_ethers = !opts.functional_class_ethers;
+                st.naming_text = Some(if opts.functional_class_ethers {
+                    "Eters: etil metil eter".to_string()
+                } else {
+                    "Eters: metoxieta".to_string()
+                });
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_F2) {
+                let opts = &mut st.naming_options;
+                opts.ortho_meta_para = !opts.ortho_meta_para;
+                st.naming_text = Some(if opts.ortho_meta_para {
+                    "Benzens: o-diclorobenze".to_string()
+                } else {
+                    "Benzens: 1,2-diclorobenze".to_string()
+                });
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_F3) {
+                let opts = &mut st.naming_options;
+                let (seguent, text) = match opts.trivial_names {
+                    TrivialNames::Hidden => {
+                        (TrivialNames::Alongside, "Noms propis: propanona (acetona)")
+                    }
+                    TrivialNames::Alongside => (TrivialNames::Instead, "Noms propis: acetona"),
+                    TrivialNames::Instead => (TrivialNames::Hidden, "Noms propis: propanona"),
+                };
+                opts.trivial_names = seguent;
+                st.naming_text = Some(text.to_string());
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_F4) {
+                let opts = &mut st.naming_options;
+                let (seguent, text) = match opts.language {
+                    Language::Catalan => (Language::Spanish, "Idioma: castella"),
+                    Language::Spanish => (Language::English, "Idioma: angles"),
+                    Language::English => (Language::Catalan, "Idioma: catala"),
+                };
+                opts.language = seguent;
+                st.naming_text = Some(text.to_string());
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_F5) {
+                let opts = &mut st.naming_options;
+                opts.short_substituent_names = !opts.short_substituent_names;
+                st.naming_text = Some(if opts.short_substituent_names {
+                    "Substituents: isopropil".to_string()
+                } else {
+                    "Substituents: (1-metiletil)".to_string()
+                });
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_F6) {
+                let opts = &mut st.naming_options;
+                let (seguent, text) = match opts.edition {
+                    Edition::Iupac1979 => (Edition::Iupac1993, "Edicio: 1993 (butan-2-ol)"),
+                    Edition::Iupac1993 => (Edition::Iupac2013, "Edicio: 2013"),
+                    Edition::Iupac2013 => (Edition::Iupac1979, "Edicio: 1979 (2-butanol)"),
+                };
+                opts.edition = seguent;
+                st.naming_text = Some(text.to_string());
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_F7) {
+                st.atom_palette = !st.atom_palette;
+                st.naming_text = Some(if st.atom_palette {
+                    "Paleta: atoms (C, H, N, O, S, F, Cl, Br, I)".to_string()
+                } else {
+                    "Paleta: grups".to_string()
+                });
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+                st.name_input = Some(String::new());
+                st.naming_text = Some("Nom: _".to_string());
             }
-        } else if rl.get_key_pressed().is_some()
-            || rl.is_mouse_button_down(MouseButton::MOUSE_BUTTON_LEFT)
-        {
-            st.naming_text = None
         }
 
         //  ===== Drawing and such =====
@@ -243,7 +520,12 @@ fn main() {
             }
         }
 
-        // radicals last
+        // radicals last, the molecule picked with G highlighted
+        let picked = st
+            .selected
+            .and_then(|id| st.uiblocks.iter().find(|b| b.id == id))
+            .map(|b| connected_ids(&st.uiblocks, b))
+            .unwrap_or_default();
         for block in &st.uiblocks {
             d.draw_rectangle_rounded(
                 Rectangle {
@@ -254,7 +536,11 @@ fn main() {
                 },
                 B::ROUNDNESS,
                 B::SEGMENTS,
-                Color::WHITE,
+                if picked.contains(&block.id) {
+                    Color::new(255, 236, 150, 255)
+                } else {
+                    Color::WHITE
+                },
             );
             d.draw_rectangle_rounded_lines_ex(
                 Rectangle {
@@ -278,11 +564,94 @@ fn main() {
                 Color::BLACK,
             );
 
-            for center in block.link_positions() {
-                d.draw_circle_v(center, B::LINK_CIRCLE_RADIUS, Color::ROYALBLUE);
+            let labels = block.radical.port_labels();
+            for (port, center) in block.link_positions() {
+                // Ports that aren't interchangeable get their own colour and a label
+                let color = match labels {
+                    Some(_) => PORT_COLORS[port % PORT_COLORS.len()],
+                    None => Color::ROYALBLUE,
+                };
+                d.draw_circle_v(center, B::LINK_CIRCLE_RADIUS, color);
+                if let Some(label) = labels.and_then(|ls| ls.get(port)) {
+                    d.draw_text_ex(
+                        &apl387_help,
+                        label,
+                        center
+                            + Vector2 {
+                                x: -B::LINK_CIRCLE_RADIUS,
+                                y: -B::LINK_PAD - B::LINK_CIRCLE_RADIUS,
+                            },
+                        HELP_TEXT_FONTSIZE as f32,
+                        B::SPACING,
+                        color,
+                    );
+                }
             }
         }
 
+        // Each label goes centred under its molecule
+        for (ids, label) in &st.labels {
+            let blocks = st.uiblocks.iter().filter(|b| ids.contains(&b.id));
+            let Some((left, right, bottom)) = blocks
+                .map(|b| {
+                    (
+                        b.pos.x - B::PAD_H,
+                        b.pos.x + b.dims().x + B::PAD_H,
+                        b.pos.y + b.dims().y + B::PAD_V,
+                    )
+                })
+                .reduce(|(l, r, b), (l2, r2, b2)| (l.min(l2), r.max(r2), b.max(b2)))
+            else {
+                continue;
+            };
+            let dims = apl387_help.measure_text(label, HELP_TEXT_FONTSIZE as f32, B::SPACING);
+            d.draw_text_ex(
+                &apl387_help,
+                label,
+                Vector2 {
+                    x: (left + right) / 2.0 - dims.x / 2.0,
+                    y: bottom + B::PAD_V,
+                },
+                HELP_TEXT_FONTSIZE as f32,
+                B::SPACING,
+                Color::DARKBLUE,
+            );
+        }
+
+        // The formula panel, on the right, one line per molecule
+        let panel = st.formula_panel.get_or_insert_with(|| {
+            formules(&st.uiblocks)
+                .into_iter()
+                .map(|(_, f)| match f {
+                    Ok(f) => {
+                        let massa = format!("{:.2}", f.molar_mass()).replace('.', ",");
+                        format!("{f}  {massa} g/mol")
+                    }
+                    Err(e) => e.to_string(),
+                })
+                .collect()
+        });
+        if !panel.is_empty() {
+            let text = panel.join("\n");
+            let dims = apl387_help.measure_text(&text, HELP_TEXT_FONTSIZE as f32, B::SPACING);
+            let x = st.window_dims.0 as f32 - dims.x - 10.0;
+            d.draw_rectangle(
+                x as i32 - 5,
+                0,
+                dims.x as i32 + 15,
+                dims.y as i32 + 10,
+                Color::new(255u8, 255u8, 255u8, 200u8),
+            );
+            d.draw_text_ex(
+                &apl387_help,
+                &text,
+                Vector2 { x, y: 5.0 },
+                HELP_TEXT_FONTSIZE as f32,
+                B::SPACING,
+                Color::DARKGREEN,
+            );
+        }
+
         if st.naming_text.is_none() {
             d.draw_text_ex(
                 &*apl387,
@@ -298,7 +667,8 @@ fn main() {
         }
 
         if st.is_help_up {
-            let dims = apl387_help.measure_text(&help_text, HELP_TEXT_FONTSIZE as f32, B::SPACING);
+            let help_text = &help_texts[usize::from(st.atom_palette)];
+            let dims = apl387_help.measure_text(help_text, HELP_TEXT_FONTSIZE as f32, B::SPACING);
             d.draw_rectangle(
                 0,
                 0,
@@ -308,7 +678,7 @@ fn main() {
             );
             d.draw_text_ex(
                 &apl387_help,
-                &help_text,
+                help_text,
                 Vector2 { x: 5.0, y: 5.0 },
                 HELP_TEXT_FONTSIZE as f32,
                 B::SPACING,
@@ -352,3 +722,24 @@ fn main() {
         }
     }
 }
+
+/// Writes `text` to `stem.ext`, or to `stem-2.ext`, `stem-3.ext`... if that one already exists,
+/// so an earlier save is never overwritten. Returns the path it used
+fn save_new((stem, ext): (&str, &str), text: &str) -> std::io::Result<String> {
+    let mut i = 1;
+    loop {
+        let path = match i {
+            1 => format!("{stem}.{ext}"),
+            i => format!("{stem}-{i}.{ext}"),
+        };
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path);
+        match file {
+            Ok(mut f) => return std::io::Write::write_all(&mut f, text.as_bytes()).map(|()| path),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => i += 1,
+            Err(e) => return Err(e),
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//! La molècula com a graf, sense res de la interfície. És el que anomena el nomenador i el que
+//! en surt quan es llegeix un nom
+use crate::{UiRadical, anomena::EnllacDeBlocs};
+
+/// Un enllaç entre dos grups, cada costat com a (grup, port). El port només importa als grups
+/// on els ports no són intercanviables: a l'èster (0 el carboni, 1 l'oxigen) i al benzè (el
+/// carboni de l'anell)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bond {
+    pub from: (usize, usize),
+    pub to: (usize, usize),
+    /// 1, 2 o 3
+    pub order: usize,
+}
+
+/// Una molècula: els grups (àtoms o grups funcionals, com el -COOH) i els enllaços entre ells.
+/// Els enllaços buits se consideren H
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Molecule {
+    pub groups: Vec<UiRadical>,
+    pub bonds: Vec<Bond>,
+}
+
+impl Molecule {
+    /// Afegeix un grup i en torna l'índex
+    pub fn add(&mut self, group: UiRadical) -> usize {
+        self.groups.push(group);
+        self.groups.len() - 1
+    }
+
+    /// Enllaça dos grups, cada un com a (grup, port). Si ja ho estaven pels mateixos ports,
+    /// l'enllaç puja d'ordre
+    pub fn bond(&mut self, from: (usize, usize), to: (usize, usize), order: usize) {
+        let ja_hi_es = self
+            .bonds
+            .iter_mut()
+            .find(|b| (b.from, b.to) == (from, to) || (b.from, b.to) == (to, from));
+        match ja_hi_es {
+            Some(b) => b.order += order,
+            None => self.bonds.push(Bond { from, to, order }),
+        }
+    }
+
+    /// Quants enllaços fa cada grup, comptant-ne l'ordre
+    pub fn bonds_per_group(&self) -> Vec<usize> {
+        let mut fets = vec![0; self.groups.len()];
+        for b in &self.bonds {
+            fets[b.from.0] += b.order;
+            fets[b.to.0] += b.order;
+        }
+        fets
+    }
+
+    /// El primer enllaç que cap àtom no pot fer, com un d'ordre 4 o un C amb 5 enllaços, dit
+    /// per als missatges d'error
+    pub fn valence_problem(&self) -> Option<String> {
+        if let Some(b) = self.bonds.iter().find(|b| b.order > 3) {
+            let (a, z) = (self.groups[b.from.0], self.groups[b.to.0]);
+            return Some(format!("un enllaç d'ordre {} entre {a} i {z}", b.order));
+        }
+        self.groups
+            .iter()
+            .zip(self.bonds_per_group())
+            .find(|(r, fets)| *fets > r.valencia() as usize)
+            .map(|(r, fets)| format!("un {r} amb {fets} enllaços"))
+    }
+
+    /// Les molècules separades que hi ha, cada una amb els índexs que hi tenien els seus grups,
+    /// en l'ordre en què apareix el seu primer grup
+    pub fn components(&self) -> Vec<(Vec<usize>, Molecule)> {
+        let mut veins = vec![vec![]; self.groups.len()];
+        for b in &self.bonds {
+            veins[b.from.0].push(b.to.0);
+            veins[b.to.0].push(b.from.0);
+        }
+        let mut vist = vec![false; self.groups.len()];
+        let mut components: Vec<Vec<usize>> = vec![];
+        for i in 0..self.groups.len() {
+            if vist[i] {
+                continue;
+            }
+            let mut grups = vec![];
+            let mut pila = vec![i];
+            while let Some(g) = pila.pop() {
+                if !vist[g] {
+                    vist[g] = true;
+                    grups.push(g);
+                    pila.extend(&veins[g]);
+                }
+            }
+            grups.sort();
+            components.push(grups);
+        }
+
+        components
+            .into_iter()
+            .map(|grups| {
+                let nou = |g: usize| grups.binary_search(&g).expect("bonds stay in a component");
+                let molecula = Molecule {
+                    groups: grups.iter().map(|&g| self.groups[g]).collect(),
+                    bonds: self
+                        .bonds
+                        .iter()
+                        .filter(|b| grups.binary_search(&b.from.0).is_ok())
+                        .map(|b| Bond {
+                            from: (nou(b.from.0), b.from.1),
+                            to: (nou(b.to.0), b.to.1),
+                            order: b.order,
+                        })
+                        .collect(),
+                };
+                (grups, molecula)
+            })
+            .collect()
+    }
+
+    /// A partir d'enllaços simples, ((grup, port), (grup, port)), on un doble enllaç hi és dos
+    /// cops
+    pub(crate) fn from_links(groups: &[UiRadical], links: &[EnllacDeBlocs]) -> Self {
+        let mut m = Molecule {
+            groups: groups.to_vec(),
+            bonds: vec![],
+        };
+        for &(a, b) in links {
+            m.bond(a, b, 1);
+        }
+        m
+    }
+
+    /// Els enllaços simples, ((grup, port), (grup, port)), amb un doble enllaç dos cops
+    pub(crate) fn links(&self) -> Vec<EnllacDeBlocs> {
+        self.bonds
+            .iter()
+            .flat_map(|b| std::iter::repeat_n((b.from, b.to), b.order))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+impl Molecule {
+    /// Per als tests: els grups i els enllaços com a (grup, grup, ordre). Cada enllaç surt pel
+    /// primer port lliure de cada grup, així que el primer enllaç d'un èster és el de l'acil i
+    /// els d'un benzè van a carbonis veïns
+    pub(crate) fn de(grups: &[UiRadical], enllacos: &[(usize, usize, usize)]) -> Self {
+        let mut m = Molecule {
+            groups: grups.to_vec(),
+            bonds: vec![],
+        };
+        let mut lliure = vec![0; grups.len()];
+        for &(a, b, ordre) in enllacos {
+            m.bond((a, lliure[a]), (b, lliure[b]), ordre);
+            lliure[a] += 1;
+            lliure[b] += 1;
+        }
+        m
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use UiRadical::*;
+
+    #[test]
+    fn el_mateix_enllac_puja_d_ordre() {
+        let mut m = Molecule::default();
+        let (a, b) = (m.add(C), m.add(C));
+        m.bond((a, 0), (b, 0), 1);
+        m.bond((b, 0), (a, 0), 1);
+        let doble = Bond {
+            from: (a, 0),
+            to: (b, 0),
+            order: 2,
+        };
+        assert_eq!(m.bonds, [doble]);
+        assert_eq!(m.bonds_per_group(), [2, 2]);
+        assert_eq!(m.links().len(), 2);
+        assert_eq!(Molecule::from_links(&m.groups, &m.links()), m);
+    }
+
+    #[test]
+    fn components() {
+        // L'etanol i el clorometà, amb els grups barrejats
+        let m = Molecule::de(&[C, Cl, C, Alcohol, C], &[(0, 2, 1), (2, 3, 1), (1, 4, 1)]);
+        let components = m.components();
+        assert_eq!(components.len(), 2);
+        assert_eq!(components[0].0, [0, 2, 3]);
+        assert_eq!(
+            components[0].1,
+            Molecule::de(&[C, C, Alcohol], &[(0, 1, 1), (1, 2, 1)])
+        );
+        assert_eq!(components[1].0, [1, 4]);
+        assert_eq!(components[1].1, Molecule::de(&[Cl, C], &[(0, 1, 1)]));
+    }
+}
@@ -0,0 +1,385 @@
+//! Els fitxers MDL: el molfile V2000 i l'SDF, que són uns quants molfiles seguits. Les
+//! coordenades són les del dibuix; els grups es despleguen en àtoms i, en llegir, es tornen a
+//! plegar
+use crate::{
+    Molecule, ParseError, Structure, UiRadical as R,
+    atoms::{AtomLlegit, Element, Problema, completa, desplega, plega},
+};
+
+/// Per què no s'ha pogut llegir o escriure un molfile. `Display` en dona el missatge per a
+/// l'usuari
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MolfileError {
+    /// No hi ha res
+    Empty,
+    /// La línia, comptant des d'1, que no té el que hi hauria de tenir
+    Line(usize),
+    /// Només es llegeix el format V2000
+    V3000,
+    /// Un element que no és de cap bloc
+    Element(String),
+    /// Un àtom amb càrrega o un radical
+    Charge,
+    /// Uns àtoms que no fan cap dels blocs, o un tipus d'enllaç que no es dibuixa
+    Unsupported(String),
+    /// Un anell aromàtic que no es pot escriure amb enllaços simples i dobles
+    Aromaticity,
+    /// Un àtom amb més enllaços dels que pot fer
+    Valence(String),
+    /// Una molècula només d'H: els H van als àtoms que els porten, i sense cap altre àtom el
+    /// molfile quedaria buit
+    OnlyHydrogen,
+}
+
+impl std::fmt::Display for MolfileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "El fitxer és buit"),
+            Self::Line(l) => write!(f, "No entenc la línia {l} del molfile"),
+            Self::V3000 => write!(f, "Només sé llegir molfiles V2000"),
+            Self::Element(e) => write!(f, "No tinc cap bloc amb {e}"),
+            Self::Charge => write!(f, "Encara no sé dibuixar càrregues"),
+            Self::Unsupported(que) => write!(f, "Encara no sé fer blocs amb {que}"),
+            Self::Aromaticity => write!(f, "Un anell aromàtic sense enllaços dobles possibles"),
+            Self::Valence(que) => write!(f, "Un {que} amb massa enllaços"),
+            Self::OnlyHydrogen => write!(f, "Una molècula només d'H no té cap àtom per desar"),
+        }
+    }
+}
+
+/// Quants Å fa un enllaç al fitxer
+const LLARGADA_D_ENLLAC: f32 = 1.5;
+
+/// La molècula en molfile V2000, amb `title` a la primera línia. `positions` és on és cada
+/// grup, en llargades d'enllaç i amb la y cap avall, com a `Structure`. Els àtoms d'un grup
+/// queden al voltant del seu lloc. Una molècula només d'H no es pot escriure
+pub fn molfile_molecula(
+    molecula: &Molecule,
+    positions: &[(f32, f32)],
+    title: &str,
+) -> Result<String, MolfileError> {
+    let (atoms, de) = desplega(molecula);
+    if atoms.elements.is_empty() {
+        return Err(MolfileError::OnlyHydrogen);
+    }
+    let mut s = format!(
+        "{}\n  Organome          2D\n\n",
+        title.lines().next().unwrap_or("")
+    );
+    let enllacos: Vec<(usize, usize, usize)> = (0..atoms.elements.len())
+        .flat_map(|a| atoms.adj[a].iter().map(move |&(b, o)| (a, b, o)))
+        .filter(|(a, b, _)| a < b)
+        .collect();
+    s.push_str(&format!(
+        "{:3}{:3}  0  0  0  0  0  0  0  0999 V2000\n",
+        atoms.elements.len(),
+        enllacos.len()
+    ));
+    for (i, &(g, k)) in de.iter().enumerate() {
+        let (x, y) = lloc_de_l_atom(molecula.groups[g], positions[g], k);
+        s.push_str(&format!(
+            "{:10.4}{:10.4}{:10.4} {:<3} 0  0  0  0  0  0  0  0  0  0  0  0\n",
+            x * LLARGADA_D_ENLLAC,
+            // Sense "-0.0000"
+            -y * LLARGADA_D_ENLLAC + 0.0,
+            0.0,
+            atoms.elements[i].simbol()
+        ));
+    }
+    for (a, b, o) in enllacos {
+        s.push_str(&format!("{:3}{:3}{:3}  0\n", a + 1, b + 1, o));
+    }
+    s.push_str("M  END\n");
+    Ok(s)
+}
+
+/// On va l'àtom `k` d'un grup que és a `pos`. El primer, al lloc del grup; els del benzè, en
+/// hexàgon; l'O de l'alquil de l'èster, cap al seu port; els altres, a dalt i a baix
+fn lloc_de_l_atom(r: R, pos: (f32, f32), k: usize) -> (f32, f32) {
+    let despla = |graus: f32, d: f32| {
+        let (sin, cos) = graus.to_radians().sin_cos();
+        (pos.0 + d * cos, pos.1 + d * sin)
+    };
+    match (r, k) {
+        // Com els ports, el primer a dalt
+        (R::Benze, k) => despla(-90.0 + 60.0 * k as f32, 0.5),
+        (_, 0) => pos,
+        (R::Ester, 2) => despla(0.0, 0.5),
+        (_, 1) => despla(-90.0, 0.5),
+        (_, _) => despla(90.0, 0.5),
+    }
+}
+
+/// Llegeix un molfile V2000 i en fa els blocs, amb les coordenades del fitxer. Els àtoms que
+/// formen un grup es tornen a ajuntar en el seu bloc. Els H explícits es deixen estar: els
+/// blocs ja en porten
+pub fn parse_molfile(text: &str) -> Result<Structure, MolfileError> {
+    let linies: Vec<&str> = text.lines().collect();
+    if linies.iter().all(|l| l.trim().is_empty()) {
+        return Err(MolfileError::Empty);
+    }
+    let comptes = linies.get(3).ok_or(MolfileError::Line(4))?;
+    if comptes.contains("V3000") {
+        return Err(MolfileError::V3000);
+    }
+    let nombre = |l: usize, de: usize, fins: usize| -> Result<usize, MolfileError> {
+        camp(linies.get(l).ok_or(MolfileError::Line(l + 1))?, de, fins)
+            .parse()
+            .map_err(|_| MolfileError::Line(l + 1))
+    };
+    let (n_atoms, n_enllacos) = (nombre(3, 0, 3)?, nombre(3, 3, 6)?);
+
+    // L'índex de cada àtom entre els que no són H
+    let mut index = vec![];
+    let mut atoms: Vec<AtomLlegit> = vec![];
+    let mut coordenades = vec![];
+    for l in 4..4 + n_atoms {
+        let linia = linies.get(l).ok_or(MolfileError::Line(l + 1))?;
+        let coordenada = |de, fins| {
+            camp(linia, de, fins)
+                .parse::<f32>()
+                .map_err(|_| MolfileError::Line(l + 1))
+        };
+        let (x, y) = (coordenada(0, 10)?, coordenada(10, 20)?);
+        let simbol = camp(linia, 31, 34);
+        if !matches!(camp(linia, 36, 39), "" | "0") {
+            return Err(MolfileError::Charge);
+        }
+        if simbol == "H" {
+            index.push(None);
+            continue;
+        }
+        let e = Element::TOTS
+            .into_iter()
+            .find(|e| e.simbol() == simbol)
+            .ok_or_else(|| MolfileError::Element(simbol.to_string()))?;
+        index.push(Some(atoms.len()));
+        atoms.push((e, false, None));
+        coordenades.push((x, -y));
+    }
+
+    let mut enllacos = vec![];
+    for l in 4 + n_atoms..4 + n_atoms + n_enllacos {
+        let a = nombre(l, 0, 3)?;
+        let b = nombre(l, 3, 6)?;
+        let tipus = nombre(l, 6, 9)?;
+        let (Some(&a), Some(&b)) = (index.get(a.wrapping_sub(1)), index.get(b.wrapping_sub(1)))
+        else {
+            return Err(MolfileError::Line(l + 1));
+        };
+        let (Some(a), Some(b)) = (a, b) else {
+            continue;
+        };
+        let ordre = match tipus {
+            1..=3 => Some(tipus),
+            4 => None,
+            t => return Err(MolfileError::Unsupported(format!("enllaços de tipus {t}"))),
+        };
+        if ordre.is_none() {
+            atoms[a].1 = true;
+            atoms[b].1 = true;
+        }
+        enllacos.push((a, b, ordre));
+    }
+    for linia in &linies[(4 + n_atoms + n_enllacos).min(linies.len())..] {
+        if linia.starts_with("M  END") {
+            break;
+        }
+        // "M  CHG  n  àtom càrrega  àtom càrrega ..."
+        if let Some(resta) = linia.strip_prefix("M  CHG")
+            && resta
+                .split_whitespace()
+                .skip(2)
+                .step_by(2)
+                .any(|c| c != "0")
+        {
+            return Err(MolfileError::Charge);
+        }
+    }
+    if atoms.is_empty() {
+        return Err(MolfileError::Empty);
+    }
+
+    let atoms = completa(&atoms, &enllacos).map_err(|p| match p {
+        Problema::Valencia(e) => MolfileError::Valence(e.simbol().to_string()),
+        Problema::MigFer(e) => MolfileError::Unsupported(format!("un {} a mig fer", e.simbol())),
+        Problema::Aromaticitat => MolfileError::Aromaticity,
+        Problema::EnllacRepetit => MolfileError::Unsupported("dos enllaços iguals".to_string()),
+    })?;
+    let plegat = plega(&atoms);
+
+    // Cada grup va al mig dels seus àtoms, i els enllaços fan 1
+    let mut enllacos_fets: Vec<f32> = enllacos
+        .iter()
+        .map(|&(a, b, _)| {
+            let (dx, dy) = (
+                coordenades[a].0 - coordenades[b].0,
+                coordenades[a].1 - coordenades[b].1,
+            );
+            (dx * dx + dy * dy).sqrt()
+        })
+        .filter(|d| *d > 1e-3)
+        .collect();
+    enllacos_fets.sort_by(f32::total_cmp);
+    let escala = enllacos_fets
+        .get(enllacos_fets.len() / 2)
+        .copied()
+        .unwrap_or(LLARGADA_D_ENLLAC);
+    let mut posicions = vec![(0.0, 0.0); plegat.grups.len()];
+    let mut quants = vec![0.0; plegat.grups.len()];
+    for (i, &g) in plegat.de.iter().enumerate() {
+        posicions[g].0 += coordenades[i].0 / escala;
+        posicions[g].1 += coordenades[i].1 / escala;
+        quants[g] += 1.0;
+    }
+    for (p, q) in posicions.iter_mut().zip(&quants) {
+        *p = (p.0 / q, p.1 / q);
+    }
+
+    plegat.estructura(&posicions).map_err(|e| match e {
+        ParseError::Valence(que) => MolfileError::Valence(que),
+        e => MolfileError::Unsupported(e.to_string()),
+    })
+}
+
+/// Llegeix cada registre d'un SDF, en ordre. Les dades que hi ha després del molfile no es
+/// fan servir
+pub fn parse_sdf(text: &str) -> Vec<Result<Structure, MolfileError>> {
+    text.split("$$$$")
+        .enumerate()
+        .map(|(i, registre)| {
+            // Només el salt de línia del "$$$$": el títol pot ser una línia buida
+            let salt = registre
+                .strip_prefix("\r\n")
+                .or_else(|| registre.strip_prefix('\n'));
+            match salt {
+                Some(resta) if i > 0 => resta,
+                _ => registre,
+            }
+        })
+        .filter(|registre| !registre.trim().is_empty())
+        .map(parse_molfile)
+        .collect()
+}
+
+/// Les molècules en SDF, cada una amb el seu títol i les seves posicions. Falla com
+/// `molfile_molecula` si alguna no es pot escriure
+pub fn sdf_molecules<'a>(
+    molecules: impl IntoIterator<Item = (&'a Molecule, &'a [(f32, f32)], &'a str)>,
+) -> Result<String, MolfileError> {
+    molecules
+        .into_iter()
+        .map(|(m, posicions, titol)| Ok(molfile_molecula(m, posicions, titol)? + "$$$$\n"))
+        .collect()
+}
+
+/// Les columnes `de..fins` d'una línia, sense espais
+fn camp(linia: &str, de: usize, fins: usize) -> &str {
+    linia.get(de..fins.min(linia.len())).unwrap_or("").trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::son_equivalents;
+
+    #[test]
+    fn molfile_v2000() {
+        let etanol = Molecule::de(&[R::C, R::C, R::Alcohol], &[(0, 1, 1), (1, 2, 1)]);
+        let text =
+            molfile_molecula(&etanol, &[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0)], "etanol").unwrap();
+        let linies: Vec<&str> = text.lines().collect();
+        assert_eq!(linies[0], "etanol");
+        assert_eq!(&linies[3][..6], "  3  2");
+        assert!(linies[3].ends_with("V2000"));
+        assert_eq!(
+            linies[5],
+            "    1.5000    0.0000    0.0000 C   0  0  0  0  0  0  0  0  0  0  0  0"
+        );
+        assert_eq!(linies[7], "  1  2  1  0");
+        assert_eq!(linies.last(), Some(&"M  END"));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(parse_molfile("\n\n\n"), Err(MolfileError::Empty));
+        let v3000 = "\n\n\n  0  0  0     0  0            999 V3000\n";
+        assert_eq!(parse_molfile(v3000), Err(MolfileError::V3000));
+        let carrega = "\n\n\n  1  0  0  0  0  0  0  0  0  0999 V2000
+    0.0000    0.0000    0.0000 N   0  3  0  0  0  0  0  0  0  0  0  0
+M  END
+";
+        assert_eq!(parse_molfile(carrega), Err(MolfileError::Charge));
+        let si = carrega.replace("N   0  3", "Si  0  0");
+        assert_eq!(
+            parse_molfile(&si),
+            Err(MolfileError::Element("Si".to_string()))
+        );
+        let sense_atoms = &carrega[..carrega.find("    0.0000").unwrap()];
+        assert_eq!(parse_molfile(sense_atoms), Err(MolfileError::Line(5)));
+        // Només d'H no hi hauria res per llegir, i no s'escriu
+        let h = Molecule::de(&[R::H], &[]);
+        assert_eq!(
+            molfile_molecula(&h, &[(0.0, 0.0)], ""),
+            Err(MolfileError::OnlyHydrogen)
+        );
+        let h2 = Molecule::de(&[R::H, R::H], &[(0, 1, 1)]);
+        let posicions = [(0.0, 0.0), (1.0, 0.0)];
+        assert_eq!(
+            sdf_molecules([(&h2, &posicions[..], "")]),
+            Err(MolfileError::OnlyHydrogen)
+        );
+    }
+
+    /// Unes quantes molècules, cada una amb els grups en filera
+    fn molecules() -> Vec<(Molecule, Vec<(f32, f32)>)> {
+        [
+            Molecule::de(&[R::C, R::C, R::Alcohol], &[(0, 1, 1), (1, 2, 1)]),
+            Molecule::de(&[R::C, R::Ester, R::C], &[(0, 1, 1), (1, 2, 1)]),
+            Molecule::de(&[R::Cl, R::Benze, R::Nitril], &[(0, 1, 1), (1, 2, 1)]),
+            Molecule::de(&[R::C, R::C, R::C], &[(0, 1, 2), (1, 2, 1)]),
+            Molecule::de(&[R::Cetona, R::N], &[(0, 1, 2)]),
+            Molecule::de(&[R::O, R::H], &[(0, 1, 1)]),
+            // Un naftalè, que en tornar un anell es plega en un benzè amb els dobles enllaços
+            // d'una altra manera
+            Molecule::de(
+                &[R::C; 10],
+                &[
+                    (0, 1, 2),
+                    (1, 2, 1),
+                    (2, 3, 2),
+                    (3, 4, 1),
+                    (4, 5, 2),
+                    (5, 0, 1),
+                    (4, 6, 1),
+                    (6, 7, 2),
+                    (7, 8, 1),
+                    (8, 9, 2),
+                    (9, 5, 1),
+                ],
+            ),
+        ]
+        .into_iter()
+        .map(|m| {
+            let posicions = (0..m.groups.len()).map(|i| (i as f32, 0.0)).collect();
+            (m, posicions)
+        })
+        .collect()
+    }
+
+    #[test]
+    fn anada_i_tornada() {
+        for (m, posicions) in molecules() {
+            let text = molfile_molecula(&m, &posicions, "").unwrap();
+            let tornada = parse_molfile(&text).unwrap_or_else(|e| panic!("{text}: {e:?}"));
+            assert!(son_equivalents(&m, &tornada.molecule), "{text}");
+        }
+        let totes = molecules();
+        let sdf = sdf_molecules(totes.iter().map(|(m, p)| (m, &p[..], "títol"))).unwrap();
+        let llegides = parse_sdf(&sdf);
+        assert_eq!(llegides.len(), totes.len());
+        for ((m, _), llegida) in totes.iter().zip(llegides) {
+            assert!(son_equivalents(m, &llegida.unwrap().molecule));
+        }
+    }
+}
@@ -0,0 +1,576 @@
+//! SMILES: l'escriptor canònic i el lector. Per escriure, els grups es despleguen en àtoms; en
+//! llegir, els àtoms es tornen a plegar en els grups que es pugui
+use crate::{
+    Molecule, ParseError, Structure, UiRadical,
+    atoms::{
+        AROMATIC, AtomLlegit, Atoms, Element, EnllacLlegit, Problema, classes, classifica,
+        completa, desplega, plega,
+    },
+};
+
+/// Per què no s'ha pogut llegir un SMILES. `Display` en dona el missatge per a l'usuari
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SmilesError {
+    /// No hi ha res
+    Empty,
+    /// El tros del SMILES, a partir d'on no l'entenem
+    Unknown(String),
+    /// Un parèntesi o un anell que no es tanca
+    Unclosed,
+    /// Un anell aromàtic que no es pot escriure amb enllaços simples i dobles
+    Aromaticity,
+    /// Uns àtoms que no es poden dibuixar amb blocs, com un àtom a mig fer
+    Unsupported(String),
+    /// Un àtom amb més enllaços dels que pot fer
+    Valence(String),
+}
+
+impl std::fmt::Display for SmilesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Escriu un SMILES"),
+            Self::Unknown(resta) => write!(f, "No entenc \"{resta}\""),
+            Self::Unclosed => write!(f, "Hi ha un parèntesi o un anell sense tancar"),
+            Self::Aromaticity => write!(f, "Un anell aromàtic sense enllaços dobles possibles"),
+            Self::Unsupported(que) => write!(f, "Encara no sé fer blocs amb {que}"),
+            Self::Valence(que) => write!(f, "Un {que} amb massa enllaços"),
+        }
+    }
+}
+
+/// El SMILES canònic de la molècula: el mateix per a la mateixa molècula, la dibuixi qui la
+/// dibuixi. Els benzens van en aromàtic, "c1ccccc1"
+pub fn smiles_molecula(molecula: &Molecule) -> String {
+    let (mut atoms, _) = desplega(molecula);
+    let n = atoms.elements.len();
+    let aromatic = atoms.aromatitza();
+    let rang = rangs(&atoms, &aromatic);
+
+    let mut escrits = vec![false; n];
+    let mut trossos = vec![];
+    while let Some(inici) = (0..n).filter(|&i| !escrits[i]).min_by_key(|&i| rang[i]) {
+        let mut e = Escriptor {
+            atoms: &atoms,
+            aromatic: &aromatic,
+            rang: &rang,
+            vist: vec![false; n],
+            fills: vec![vec![]; n],
+            anells: vec![],
+            oberts: vec![],
+            s: String::new(),
+        };
+        e.recorre(inici, None);
+        e.escriu(inici, &mut escrits);
+        trossos.push(e.s);
+    }
+    // Els H que no van amb cap altre àtom no es despleguen, i s'escriuen tal com són: "[H][H]"
+    for (grups, _) in molecula.components() {
+        if grups.iter().all(|&g| molecula.groups[g] == UiRadical::H) {
+            trossos.push("[H]".repeat(grups.len()));
+        }
+    }
+    trossos.sort();
+    trossos.join(".")
+}
+
+/// Llegeix un SMILES i en fa els blocs. Els àtoms que formen un grup (C(=O)O, C#N, c1ccccc1...)
+/// es tornen a ajuntar en el seu bloc
+pub fn parse_smiles(smiles: &str) -> Result<Structure, SmilesError> {
+    let smiles = smiles.trim();
+    if smiles.is_empty() {
+        return Err(SmilesError::Empty);
+    }
+    let atoms = llegeix(smiles)?;
+    let plegat = plega(&atoms);
+
+    let posicions = disposa(&plegat.veins());
+    plegat.estructura(&posicions).map_err(|e| match e {
+        ParseError::Valence(que) => SmilesError::Valence(que),
+        e => SmilesError::Unsupported(e.to_string()),
+    })
+}
+
+fn parella(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+/// El rang canònic de cada àtom: les classes d'`Atoms::classes_equivalents`, amb els empats
+/// que queden desfets d'un en un
+fn rangs(atoms: &Atoms, aromatic: &[bool]) -> Vec<usize> {
+    let n = atoms.elements.len();
+    let mut rang = atoms.classes_equivalents(aromatic);
+    while classes(&rang) < n {
+        // El primer empat es desfa triant el primer dels àtoms que empaten
+        let empatat = (0..n)
+            .filter(|&r| rang.iter().filter(|&&x| x == r).count() > 1)
+            .min()
+            .expect("some rank is shared");
+        let triat = rang
+            .iter()
+            .position(|&x| x == empatat)
+            .expect("rank exists");
+        let doblats: Vec<usize> = (0..n)
+            .map(|i| 2 * rang[i] + usize::from(i != triat || rang[i] != empatat))
+            .collect();
+        rang = atoms.refina(classifica(&doblats));
+    }
+    rang
+}
+
+struct Escriptor<'a> {
+    atoms: &'a Atoms,
+    aromatic: &'a [bool],
+    rang: &'a [usize],
+    vist: Vec<bool>,
+    /// Els veïns que es visiten des de cada àtom, per ordre
+    fills: Vec<Vec<usize>>,
+    /// Els enllaços que tanquen anells
+    anells: Vec<(usize, usize)>,
+    /// L'àtom que té obert cada número d'anell
+    oberts: Vec<Option<usize>>,
+    s: String,
+}
+
+impl Escriptor<'_> {
+    fn veins(&self, i: usize) -> Vec<usize> {
+        let mut veins: Vec<usize> = self.atoms.adj[i].iter().map(|(v, _)| *v).collect();
+        veins.sort_by_key(|&v| self.rang[v]);
+        veins
+    }
+
+    /// Primer es recorre la molècula, per saber quins enllaços tanquen anells
+    fn recorre(&mut self, i: usize, pare: Option<usize>) {
+        self.vist[i] = true;
+        for v in self.veins(i) {
+            if Some(v) == pare {
+                continue;
+            }
+            if self.vist[v] {
+                if !self.anells.contains(&parella(i, v)) {
+                    self.anells.push(parella(i, v));
+                }
+            } else {
+                self.fills[i].push(v);
+                self.recorre(v, Some(i));
+            }
+        }
+    }
+
+    fn enllac(&self, a: usize, b: usize) -> &'static str {
+        match self.atoms.ordre(a, b) {
+            AROMATIC => "",
+            // Entre dos aromàtics, el simple s'ha de dir
+            1 if self.aromatic[a] && self.aromatic[b] => "-",
+            2 => "=",
+            3 => "#",
+            _ => "",
+        }
+    }
+
+    fn escriu(&mut self, i: usize, escrits: &mut [bool]) {
+        escrits[i] = true;
+        let simbol = self.atoms.elements[i].simbol();
+        match self.aromatic[i] {
+            true => self.s.push_str(&simbol.to_lowercase()),
+            false => self.s.push_str(simbol),
+        }
+
+        let mut tanca = vec![];
+        let mut obre = vec![];
+        for v in self.veins(i) {
+            if self.anells.contains(&parella(i, v)) {
+                match escrits[v] {
+                    true => tanca.push(v),
+                    false => obre.push(v),
+                }
+            }
+        }
+        for v in tanca {
+            let k = self
+                .oberts
+                .iter()
+                .position(|o| *o == Some(v))
+                .expect("ring was opened");
+            self.oberts[k] = None;
+            self.s.push_str(&numero_d_anell(k + 1));
+        }
+        for v in obre {
+            let k = match self.oberts.iter().position(Option::is_none) {
+                Some(k) => k,
+                None => {
+                    self.oberts.push(None);
+                    self.oberts.len() - 1
+                }
+            };
+            self.oberts[k] = Some(i);
+            let enllac = self.enllac(i, v);
+            self.s.push_str(enllac);
+            self.s.push_str(&numero_d_anell(k + 1));
+        }
+
+        let fills = self.fills[i].clone();
+        for (k, &v) in fills.iter().enumerate() {
+            let branca = k + 1 < fills.len();
+            if branca {
+                self.s.push('(');
+            }
+            let enllac = self.enllac(i, v);
+            self.s.push_str(enllac);
+            self.escriu(v, escrits);
+            if branca {
+                self.s.push(')');
+            }
+        }
+    }
+}
+
+fn numero_d_anell(k: usize) -> String {
+    match k {
+        1..=9 => k.to_string(),
+        k => format!("%{k}"),
+    }
+}
+
+/// Com és un enllaç escrit
+#[derive(Clone, Copy, PartialEq)]
+enum Enllac {
+    /// Sense símbol: simple, o aromàtic entre dos aromàtics
+    Implicit,
+    Ordre(usize),
+    Aromatic,
+}
+
+/// Llegeix els àtoms i els enllaços, i passa els anells aromàtics a enllaços simples i dobles
+fn llegeix(smiles: &str) -> Result<Atoms, SmilesError> {
+    let desconegut = |pos: usize| SmilesError::Unknown(smiles[pos..].to_string());
+    // (element, aromàtic, H si van entre claudàtors)
+    let mut atoms: Vec<AtomLlegit> = vec![];
+    let mut enllacos: Vec<(usize, usize, Enllac)> = vec![];
+    let mut anterior: Option<usize> = None;
+    let mut branques = vec![];
+    let mut enllac: Option<Enllac> = None;
+    let mut anells: Vec<(usize, usize, Option<Enllac>)> = vec![];
+
+    let mut pos = 0;
+    while pos < smiles.len() {
+        let resta = &smiles[pos..];
+        let c = resta.chars().next().expect("not at the end");
+        let mut atom = None;
+        let mut tancament = None;
+        match c {
+            '(' => branques.push(anterior.ok_or_else(|| desconegut(pos))?),
+            ')' => anterior = Some(branques.pop().ok_or_else(|| desconegut(pos))?),
+            '.' => anterior = None,
+            '-' | '/' | '\\' => enllac = Some(Enllac::Ordre(1)),
+            '=' => enllac = Some(Enllac::Ordre(2)),
+            '#' => enllac = Some(Enllac::Ordre(3)),
+            ':' => enllac = Some(Enllac::Aromatic),
+            '0'..='9' => tancament = Some((resta[..1].parse().expect("a digit"), 1)),
+            '%' => {
+                let xifres = resta
+                    .get(1..3)
+                    .filter(|x| x.bytes().all(|b| b.is_ascii_digit()));
+                let x = xifres.ok_or_else(|| desconegut(pos))?;
+                tancament = Some((x.parse().expect("two digits"), 3));
+            }
+            '[' => {
+                let fi = resta.find(']').ok_or(SmilesError::Unclosed)?;
+                let (e, aromatic, h) = claudator(&resta[1..fi]).ok_or_else(|| desconegut(pos))?;
+                atom = Some((e, aromatic, Some(h), fi + 1));
+            }
+            _ => {
+                let (e, aromatic, llargada) = organic(resta).ok_or_else(|| desconegut(pos))?;
+                atom = Some((e, aromatic, None, llargada));
+            }
+        }
+
+        if let Some((numero, llargada)) = tancament {
+            let i = anterior.ok_or_else(|| desconegut(pos))?;
+            match anells.iter().position(|(n, ..)| *n == numero) {
+                Some(k) => {
+                    let (_, j, primer) = anells.remove(k);
+                    let tipus = enllac.take().or(primer).unwrap_or(Enllac::Implicit);
+                    enllacos.push((j, i, tipus));
+                }
+                None => anells.push((numero, i, enllac.take())),
+            }
+            pos += llargada;
+            continue;
+        }
+        if let Some((e, aromatic, h, llargada)) = atom {
+            atoms.push((e, aromatic, h));
+            let i = atoms.len() - 1;
+            if let Some(a) = anterior {
+                enllacos.push((a, i, enllac.take().unwrap_or(Enllac::Implicit)));
+            }
+            anterior = Some(i);
+            pos += llargada;
+            continue;
+        }
+        pos += c.len_utf8();
+    }
+    if !branques.is_empty() || !anells.is_empty() {
+        return Err(SmilesError::Unclosed);
+    }
+    if enllac.is_some() {
+        return Err(SmilesError::Unknown(smiles.to_string()));
+    }
+
+    let enllacos: Vec<_> = enllacos
+        .into_iter()
+        .map(|(a, b, e)| match e {
+            Enllac::Ordre(o) => (a, b, Some(o)),
+            Enllac::Implicit if !(atoms[a].1 && atoms[b].1) => (a, b, Some(1)),
+            Enllac::Implicit | Enllac::Aromatic => (a, b, None),
+        })
+        .collect();
+    let (atoms, enllacos, h_treta) = treu_hidrogens(atoms, enllacos);
+    let complet = completa(&atoms, &enllacos).map_err(|p| match p {
+        Problema::Valencia(e) => SmilesError::Valence(e.simbol().to_string()),
+        Problema::MigFer(e) => SmilesError::Unsupported(format!("un {} a mig fer", e.simbol())),
+        Problema::Aromaticitat => SmilesError::Aromaticity,
+        Problema::EnllacRepetit => SmilesError::Unknown(smiles.to_string()),
+    })?;
+    // Un àtom fora de claudàtors no pot dur més "[H]" que els H que li caben
+    match (0..atoms.len()).find(|&i| h_treta[i] > complet.h[i]) {
+        Some(i) => Err(SmilesError::Valence(atoms[i].0.simbol().to_string())),
+        None => Ok(complet),
+    }
+}
+
+/// Treu els "[H]" enllaçats a un altre àtom, que en du un H més, com fa el molfile, i torna
+/// quants n'ha tret de cada àtom. Els que no en tenen cap altre al costat, com a "[H][H]", es
+/// queden
+fn treu_hidrogens(
+    atoms: Vec<AtomLlegit>,
+    enllacos: Vec<EnllacLlegit>,
+) -> (Vec<AtomLlegit>, Vec<EnllacLlegit>, Vec<usize>) {
+    let es_h = |i: usize| atoms[i].0 == Element::H;
+    let mut sobra = vec![false; atoms.len()];
+    for (i, atom) in atoms.iter().enumerate() {
+        if atom.0 != Element::H {
+            continue;
+        }
+        let mut seus = enllacos.iter().filter(|(a, b, _)| *a == i || *b == i);
+        if let (Some(&(a, b, Some(1))), None) = (seus.next(), seus.next()) {
+            sobra[i] = !es_h(a + b - i);
+        }
+    }
+    let mut index = vec![None; atoms.len()];
+    let mut queden = vec![];
+    for (i, &atom) in atoms.iter().enumerate() {
+        if !sobra[i] {
+            index[i] = Some(queden.len());
+            queden.push(atom);
+        }
+    }
+    let mut resta = vec![];
+    let mut trets = vec![0; queden.len()];
+    for (a, b, o) in enllacos {
+        match (index[a], index[b]) {
+            (Some(a), Some(b)) => resta.push((a, b, o)),
+            (Some(v), None) | (None, Some(v)) => {
+                trets[v] += 1;
+                // Si no va entre claudàtors, els H ja s'hi posen sols
+                if let Some(h) = &mut queden[v].2 {
+                    *h += 1;
+                }
+            }
+            (None, None) => unreachable!("an H bonded to an H is kept"),
+        }
+    }
+    (queden, resta, trets)
+}
+
+/// Un àtom del subconjunt orgànic, que no va entre claudàtors: (element, aromàtic, llargada)
+fn organic(s: &str) -> Option<(Element, bool, usize)> {
+    // Els de dues lletres primer, que "Cl" no és un C
+    let mut elements = Element::TOTS;
+    elements.sort_by_key(|e| std::cmp::Reverse(e.simbol().len()));
+    for e in elements {
+        if s.starts_with(e.simbol()) {
+            return Some((e, false, e.simbol().len()));
+        }
+        if e.valencia() > 1 && s.starts_with(&e.simbol().to_lowercase()) {
+            return Some((e, true, e.simbol().len()));
+        }
+    }
+    None
+}
+
+/// L'interior d'un àtom entre claudàtors, com "NH2" o "C@@H": (element, aromàtic, H). Els
+/// isòtops i la quiralitat no es poden dibuixar i es deixen estar; les càrregues, no
+fn claudator(s: &str) -> Option<(Element, bool, usize)> {
+    let s = s.trim_start_matches(|c: char| c.is_ascii_digit());
+    // L'H no és del subconjunt orgànic, però entre claudàtors sí que es pot escriure
+    let (e, aromatic, llargada) =
+        organic(s).or_else(|| s.starts_with('H').then_some((Element::H, false, "H".len())))?;
+    let s = s[llargada..].trim_start_matches('@');
+    let (h, s) = match s.strip_prefix('H') {
+        Some(resta) => {
+            let xifres = resta.bytes().take_while(u8::is_ascii_digit).count();
+            (resta[..xifres].parse().unwrap_or(1), &resta[xifres..])
+        }
+        None => (0, s),
+    };
+    // La classe de l'àtom, ":1", tampoc es dibuixa
+    let s = s.split(':').next().unwrap_or_default();
+    s.is_empty().then_some((e, aromatic, h))
+}
+
+/// On va cada grup, en unitats de llargada d'enllaç: els grups queden a tanta distància com
+/// enllaços els separen (majorització de l'estrès), amb la molècula allargada en horitzontal
+fn disposa(veins: &[Vec<usize>]) -> Vec<(f32, f32)> {
+    let n = veins.len();
+    // Les distàncies pel graf; les molècules separades, una mica més lluny que la més llarga
+    let mut d = vec![vec![f32::INFINITY; n]; n];
+    for (i, di) in d.iter_mut().enumerate() {
+        di[i] = 0.0;
+        let mut cua = std::collections::VecDeque::from([i]);
+        while let Some(u) = cua.pop_front() {
+            for &v in &veins[u] {
+                if di[v].is_infinite() {
+                    di[v] = di[u] + 1.0;
+                    cua.push_back(v);
+                }
+            }
+        }
+    }
+    let maxim = d
+        .iter()
+        .flatten()
+        .copied()
+        .filter(|x| x.is_finite())
+        .fold(0.0, f32::max);
+    for x in d.iter_mut().flatten() {
+        if x.is_infinite() {
+            *x = maxim + 2.0;
+        }
+    }
+
+    let radi = n as f32 / std::f32::consts::TAU;
+    let mut p: Vec<(f32, f32)> = (0..n)
+        .map(|i| {
+            let angle = std::f32::consts::TAU * i as f32 / n as f32;
+            (radi * angle.cos(), radi * angle.sin())
+        })
+        .collect();
+    for _ in 0..300 {
+        for i in 0..n {
+            let (mut x, mut y, mut pes) = (0.0, 0.0, 0.0);
+            for j in (0..n).filter(|&j| j != i) {
+                let w = 1.0 / (d[i][j] * d[i][j]);
+                let (dx, dy) = (p[i].0 - p[j].0, p[i].1 - p[j].1);
+                let dist = (dx * dx + dy * dy).sqrt().max(1e-3);
+                x += w * (p[j].0 + d[i][j] * dx / dist);
+                y += w * (p[j].1 + d[i][j] * dy / dist);
+                pes += w;
+            }
+            if pes > 0.0 {
+                p[i] = (x / pes, y / pes);
+            }
+        }
+    }
+
+    // Gira-la perquè la direcció més llarga quedi horitzontal
+    let (mx, my) = p.iter().fold((0.0, 0.0), |(a, b), (x, y)| (a + x, b + y));
+    let (mx, my) = (mx / n as f32, my / n as f32);
+    let (mut sxx, mut syy, mut sxy) = (0.0, 0.0, 0.0);
+    for (x, y) in &p {
+        let (x, y) = (x - mx, y - my);
+        sxx += x * x;
+        syy += y * y;
+        sxy += x * y;
+    }
+    let angle = 0.5 * (2.0 * sxy).atan2(sxx - syy);
+    let (sin, cos) = (-angle).sin_cos();
+    p.iter()
+        .map(|(x, y)| {
+            let (x, y) = (x - mx, y - my);
+            (x * cos - y * sin, x * sin + y * cos)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UiRadical::*;
+    use crate::son_equivalents;
+
+    #[test]
+    fn smiles_canonic() {
+        let acetic = Molecule::de(&[C, Carboxil], &[(0, 1, 1)]);
+        assert_eq!(smiles_molecula(&acetic), "CC(O)=O");
+        assert_eq!(smiles_molecula(&Molecule::de(&[Benze], &[])), "c1ccccc1");
+        let fenol = Molecule::de(&[Alcohol, Benze], &[(0, 1, 1)]);
+        assert_eq!(smiles_molecula(&fenol), "Oc1ccccc1");
+        let acetonitril = Molecule::de(&[Nitril, C], &[(0, 1, 1)]);
+        assert_eq!(smiles_molecula(&acetonitril), "CC#N");
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(parse_smiles("  "), Err(SmilesError::Empty));
+        assert_eq!(parse_smiles("CC(C"), Err(SmilesError::Unclosed));
+        assert_eq!(parse_smiles("C1CC"), Err(SmilesError::Unclosed));
+        assert!(matches!(parse_smiles("CCX"), Err(SmilesError::Unknown(_))));
+        assert_eq!(parse_smiles("c1cccc1"), Err(SmilesError::Aromaticity));
+        assert!(matches!(
+            parse_smiles("C(C)(C)(C)(C)C"),
+            Err(SmilesError::Valence(_))
+        ));
+    }
+
+    #[test]
+    fn anada_i_tornada() {
+        // Un SMILES qualsevol torna com el canònic, i el canònic torna igual
+        let acetat = parse_smiles("CC(=O)OC").unwrap().molecule;
+        assert_eq!(smiles_molecula(&acetat), "COC(C)=O");
+        let tornada = parse_smiles("COC(C)=O").unwrap().molecule;
+        assert!(son_equivalents(&acetat, &tornada));
+        assert_eq!(smiles_molecula(&tornada), "COC(C)=O");
+
+        let molecules = [
+            Molecule::de(&[C, Carboxil], &[(0, 1, 1)]),
+            Molecule::de(&[Alcohol, Benze], &[(0, 1, 1)]),
+            Molecule::de(&[Nitril, C], &[(0, 1, 1)]),
+            Molecule::de(&[C, Cetona, C], &[(0, 1, 1), (1, 2, 1)]),
+            Molecule::de(&[C, Eter, C, C], &[(0, 1, 1), (1, 2, 1), (2, 3, 1)]),
+            Molecule::de(&[Cl, C, C, Amida], &[(0, 1, 1), (1, 2, 1), (2, 3, 1)]),
+            Molecule::de(&[C, C, C], &[(0, 1, 2), (1, 2, 1)]),
+            // HN=C=O: el carbonil sense H torna com a `Cetona`
+            Molecule::de(&[Cetona, N], &[(0, 1, 2)]),
+            Molecule::de(&[H, H], &[(0, 1, 1)]),
+        ];
+        for m in molecules {
+            let smiles = smiles_molecula(&m);
+            let tornada = parse_smiles(&smiles).unwrap_or_else(|e| panic!("{smiles}: {e:?}"));
+            assert!(son_equivalents(&m, &tornada.molecule), "{smiles}");
+            assert_eq!(smiles_molecula(&tornada.molecule), smiles);
+        }
+    }
+
+    #[test]
+    fn hidrogens_explicits() {
+        // Un [H] és un H més per a l'àtom on va, entre claudàtors o no
+        for smiles in ["CC(=O)O[H]", "[H]OC(C)=O", "C[C](=O)[OH0][H]"] {
+            let m = parse_smiles(smiles).unwrap().molecule;
+            assert_eq!(smiles_molecula(&m), "CC(O)=O", "{smiles}");
+        }
+        let metanol = parse_smiles("[H]C([H])([H])O").unwrap().molecule;
+        assert_eq!(smiles_molecula(&metanol), "CO");
+        let benze = parse_smiles("[H]c1ccccc1").unwrap().molecule;
+        assert_eq!(smiles_molecula(&benze), "c1ccccc1");
+        // Sense cap altre àtom, els H es queden
+        let h2 = parse_smiles("[H][H]").unwrap().molecule;
+        assert_eq!(h2, Molecule::de(&[H, H], &[(0, 1, 1)]));
+        assert_eq!(smiles_molecula(&h2), "[H][H]");
+        let amb_h2 = parse_smiles("[H][H].CC").unwrap().molecule;
+        assert_eq!(smiles_molecula(&amb_h2), "CC.[H][H]");
+        assert!(matches!(
+            parse_smiles("C([H])([H])([H])([H])[H]"),
+            Err(SmilesError::Valence(_))
+        ));
+    }
+}
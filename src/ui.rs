@@ -0,0 +1,752 @@
+//! The canvas: blocks, links, undo and the glue between them and the naming engine
+
+use crate::*;
+use raylib::prelude::*;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+pub type Id = u128;
+pub const LINK_MARGIN_BETWEEN_RADICAL: f32 = 10.0;
+pub const LINK_LINE_THICKNESS: f32 = 3.0;
+
+pub struct UiState {
+    pub uiblocks: Vec<UiBlock>,
+    pub held: Option<Held>,
+    pub is_help_up: bool,
+    /// for undoing (DO NOT PUSH TO MANUALLY, USE `push_to_undo`)
+    pub undo_list: Vec<UiAction>,
+    /// for unundoing (DO NOT PUSH TO MANUALLY, USE `redo_last`)
+    pub redo_list: Vec<UiAction>,
+    /// (width, height)
+    pub window_dims: (i32, i32),
+    /// Text that shows the name of the molecule
+    pub naming_text: Option<String>,
+    pub naming_options: NamingOptions,
+    /// The name being typed in, to draw its molecule when done
+    pub name_input: Option<String>,
+    /// Names drawn next to each molecule, with the ids of its blocks. Any change but a move
+    /// makes them stale, so they are dropped
+    pub labels: Vec<(Vec<Id>, String)>,
+    /// The lines of the formula panel, one per molecule. Dropped like `labels`, and worked out
+    /// again only when it is drawn
+    pub formula_panel: Option<Vec<String>>,
+    /// Whether the letter keys add single atoms (H, N, O, S...) instead of groups
+    pub atom_palette: bool,
+    /// A block of the molecule picked with G, to compare with the next one
+    pub selected: Option<Id>,
+}
+
+impl UiState {
+    pub fn push_to_undo(&mut self, a: UiAction) {
+        if !matches!(a, UiAction::MoveRadicals(_)) {
+            self.labels.clear();
+            self.formula_panel = None;
+        }
+        self.undo_list.push(a);
+        self.redo_list.clear();
+    }
+
+    /// Draws a molecule read from a name or a file centred on `center`, one undo step per block
+    /// and link. Returns how wide it is drawn, in pixels
+    pub fn add_structure(&mut self, s: &Structure, font: Rc<Font>, center: Vector2) -> f32 {
+        let blocks = structure_blocks(s, font, center);
+        for b in &blocks {
+            self.push_to_undo(UiAction::AddRadical(UiBlock {
+                links: vec![],
+                ..b.clone()
+            }));
+        }
+        for bond in &s.molecule.bonds {
+            let ((a, a_port), (b, b_port)) = (bond.from, bond.to);
+            for _ in 0..bond.order {
+                self.push_to_undo(UiAction::AddLink(
+                    (blocks[a].id, a_port),
+                    (blocks[b].id, b_port),
+                ));
+            }
+        }
+        let xs = blocks.iter().map(|b| b.pos.x);
+        let width = xs.clone().fold(f32::NEG_INFINITY, f32::max) - xs.fold(f32::INFINITY, f32::min);
+        self.uiblocks.extend(blocks);
+        width
+    }
+}
+
+/// A building block of the UI; a node in the network
+#[derive(Debug, Clone)]
+pub struct UiBlock {
+    pub pos: Vector2,
+    pub radical: UiRadical,
+    pub font: Rc<Font>,
+    /// (id, port): the block on the other end and which of our ports the link uses (an index
+    /// into `port_positions`). Multiple bonds are repeated entries
+    pub links: Vec<(Id, usize)>,
+    pub id: Id,
+}
+
+impl UiBlock {
+    pub const PAD_V: f32 = 15.0;
+    pub const PAD_H: f32 = 20.0;
+    pub const LINK_CIRCLE_RADIUS: f32 = 7.0;
+    pub const LINK_PAD: f32 = 10.0 + Self::LINK_CIRCLE_RADIUS;
+    pub const FONT_SIZE: i32 = 30;
+    pub const SPACING: f32 = 0.5; // I don't know what this does but raylib asks for it
+    pub const ROUNDNESS: f32 = 0.75;
+    pub const SEGMENTS: i32 = 20;
+    pub const LINE_THICKNESS: f32 = 4.0;
+
+    pub fn dims(&self) -> Vector2 {
+        let text = self.radical.to_string();
+        self.font
+            .measure_text(&text, Self::FONT_SIZE as f32, Self::SPACING)
+    }
+    pub fn center(&self) -> Vector2 {
+        let (width, height) = (self.dims().x, self.dims().y);
+        let x = self.pos.x - Self::PAD_H + f32::midpoint(Self::PAD_V * 2.0, width);
+        let y = self.pos.y - Self::PAD_V + f32::midpoint(Self::PAD_V * 2.0, height);
+        Vector2 { x, y }
+    }
+
+    /// returns [Up Left Down Right]
+    pub fn general_link_positions(&self) -> [Vector2; 4] {
+        let (width, height) = (self.dims().x, self.dims().y);
+        let Vector2 {
+            x: mid_width,
+            y: mid_height,
+        } = self.center();
+
+        let left = Vector2 {
+            x: self.pos.x - Self::PAD_H - Self::LINK_PAD,
+            y: mid_height,
+        };
+        let right = Vector2 {
+            x: self.pos.x + Self::PAD_H + Self::LINK_PAD + width,
+            y: mid_height,
+        };
+        let up = Vector2 {
+            x: mid_width,
+            y: self.pos.y - Self::PAD_V - Self::LINK_PAD,
+        };
+        let down = Vector2 {
+            x: mid_width,
+            y: self.pos.y + Self::PAD_V + Self::LINK_PAD + height,
+        };
+
+        [up, left, down, right]
+    }
+    /// Six ports around the block, clockwise from the top, so that consecutive ports are
+    /// neighbouring carbons of a benzene ring
+    pub fn hexagon_link_positions(&self) -> [Vector2; 6] {
+        let (width, height) = (self.dims().x, self.dims().y);
+        let c = self.center();
+        let (rx, ry) = (
+            width / 2.0 + Self::PAD_H + Self::LINK_PAD,
+            height / 2.0 + Self::PAD_V + Self::LINK_PAD,
+        );
+        std::array::from_fn(|k| {
+            let angle = (-90.0 + 60.0 * k as f32).to_radians();
+            Vector2 {
+                x: c.x + rx * angle.cos(),
+                y: c.y + ry * angle.sin(),
+            }
+        })
+    }
+    /// Every port the radical has, used or not. The index is what `links` stores
+    pub fn port_positions(&self) -> Vec<Vector2> {
+        let a_poss = self.general_link_positions();
+        match self.radical.valencia() {
+            1 => vec![a_poss[3]],
+            2 => vec![a_poss[1], a_poss[3]],
+            3 => vec![a_poss[0], a_poss[1], a_poss[3]],
+            4 => a_poss.to_vec(),
+            6 => self.hexagon_link_positions().to_vec(),
+            l => {
+                eprintln!("ERROR: molecula has {l} valencies, que no és ni 1 ni 2 ni 4 ni 6");
+                a_poss.to_vec()
+            }
+        }
+    }
+    /// The ports that are still free, as (port, position)
+    pub fn link_positions(&self) -> Vec<(usize, Vector2)> {
+        self.port_positions()
+            .into_iter()
+            .enumerate()
+            .filter(|(port, _)| !self.links.iter().any(|(_, p)| p == port))
+            .collect()
+    }
+    /// Which block is linked through `port`, if any
+    pub fn linked_at(&self, port: usize) -> Option<Id> {
+        self.links
+            .iter()
+            .find(|(_, p)| *p == port)
+            .map(|(id, _)| *id)
+    }
+
+    pub fn count_links(blocks: &[UiBlock]) -> HashMap<(Id, Id), usize> {
+        let mut links: HashMap<(Id, Id), usize> = HashMap::new();
+        for block in blocks {
+            for (l_id, _) in &block.links {
+                let (a, b) = (block.id.min(*l_id), block.id.max(*l_id));
+                links.entry((a, b)).and_modify(|c| *c += 1).or_insert(1);
+            }
+        }
+        for m in links.values_mut() {
+            *m /= 2;
+        } // don't double count
+        links
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Held {
+    /// `.0` is of the form Vec<(id, from)>
+    // /// `origin` is where the mouse was originally when the holding down was initiated
+    Radicals(Vec<(Id, Vector2)>),
+    Link {
+        radical: Id,
+        port: usize,
+        from: Vector2,
+    },
+    RectangleCreation {
+        from: Vector2,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub enum UiAction {
+    AddRadical(UiBlock),
+    DeleteRadical(UiBlock),
+    /// (id, from, to)
+    MoveRadicals(Vec<(Id, Vector2, Vector2)>),
+    /// ((id, port), (id, port))
+    AddLink((Id, usize), (Id, usize)),
+    /// ((id, port), (id, port))
+    DeleteLink((Id, usize), (Id, usize)),
+}
+
+impl UiAction {
+    pub fn opposite(&self) -> Self {
+        match self {
+            Self::AddRadical(what) => Self::DeleteRadical(what.clone()),
+            Self::DeleteRadical(what) => Self::AddRadical(what.clone()),
+            Self::MoveRadicals(data) => {
+                Self::MoveRadicals(data.iter().map(|(i, f, t)| (*i, *t, *f)).collect())
+            }
+            Self::AddLink(a, b) => Self::DeleteLink(*b, *a),
+            Self::DeleteLink(a, b) => Self::AddLink(*b, *a),
+        }
+    }
+}
+
+// the `raylib` library forgot to put this function in raylib::core::collision -_-
+pub fn is_point_in_rect(p: Vector2, rect: Rectangle) -> bool {
+    (p.x >= rect.x && p.x <= rect.x + rect.width) && (p.y >= rect.y && p.y <= rect.y + rect.height)
+}
+
+pub fn is_point_in_block(p: Vector2, b: &UiBlock) -> bool {
+    use UiBlock as B;
+    is_point_in_rect(
+        p,
+        Rectangle {
+            x: b.pos.x - B::PAD_H,
+            y: b.pos.y - B::PAD_V,
+            width: b.dims().x + 2.0 * B::PAD_H,
+            height: b.dims().y + 2.0 * B::PAD_H,
+        },
+    )
+}
+
+/// Pixels between neighbouring blocks of a molecule read from a name or a file, one bond length
+/// in `Structure::positions`
+const STRUCTURE_SPACING: Vector2 = Vector2 { x: 130.0, y: 100.0 };
+
+/// The blocks of a molecule read with `parse_name`, centred on `center`
+pub fn structure_blocks(s: &Structure, font: Rc<Font>, center: Vector2) -> Vec<UiBlock> {
+    let (xs, ys): (Vec<f32>, Vec<f32>) = s.positions.iter().copied().unzip();
+    let middle = |v: &[f32]| {
+        let min = v.iter().copied().fold(f32::INFINITY, f32::min);
+        let max = v.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        f32::midpoint(min, max)
+    };
+    let (mid_x, mid_y) = (middle(&xs), middle(&ys));
+
+    let mut blocks: Vec<UiBlock> = s
+        .molecule
+        .groups
+        .iter()
+        .zip(&s.positions)
+        .map(|(radical, (x, y))| UiBlock {
+            pos: Vector2 {
+                x: center.x + (x - mid_x) * STRUCTURE_SPACING.x,
+                y: center.y + (y - mid_y) * STRUCTURE_SPACING.y,
+            },
+            radical: *radical,
+            font: font.clone(),
+            links: vec![],
+            id: rand::random(),
+        })
+        .collect();
+    for ((a, a_port), (b, b_port)) in s.molecule.links() {
+        let (a_id, b_id) = (blocks[a].id, blocks[b].id);
+        blocks[a].links.push((b_id, a_port));
+        blocks[b].links.push((a_id, b_port));
+    }
+    blocks
+}
+
+pub fn get_block_under_point(bs: &[UiBlock], cursor: Vector2) -> Option<&UiBlock> {
+    bs.iter().find(|b| is_point_in_block(cursor, b))
+}
+
+pub fn remove_hanging_links(blocks: &mut Vec<UiBlock>, del_id: Id) {
+    for b in blocks {
+        b.links.retain(|&(l, _)| l != del_id)
+    }
+}
+
+/// Returns (id, port, position)
+pub fn link_node_at_point(
+    blocks: &[UiBlock],
+    curr_mouse_pos: Vector2,
+    threshold: f32,
+) -> Option<(Id, usize, Vector2)> {
+    for b in blocks {
+        for (port, c) in b.link_positions() {
+            if check_collision_point_circle(
+                curr_mouse_pos,
+                c,
+                UiBlock::LINK_CIRCLE_RADIUS + threshold,
+            ) {
+                return Some((b.id, port, c));
+            }
+        }
+    }
+    None
+}
+
+pub fn get_block_unchecked(blocks: &[UiBlock], id: Id) -> &UiBlock {
+    blocks
+        .iter()
+        .find(|b| b.id == id)
+        .unwrap_or_else(|| panic!("Block unexpectedly disappeared: {id}"))
+}
+pub fn get_two_blocks_unchecked_mut(
+    blocks: &mut [UiBlock],
+    id_a: Id,
+    id_b: Id,
+) -> (&mut UiBlock, &mut UiBlock) {
+    let a = blocks
+        .iter()
+        .position(|bl| bl.id == id_a)
+        .unwrap_or_else(|| panic!("Block unexpectedly disappeared: {id_a}"));
+    let b = blocks
+        .iter()
+        .position(|bl| bl.id == id_b)
+        .unwrap_or_else(|| panic!("Block unexpectedly disappeared: {id_b}"));
+    let [r1, r2] = blocks
+        .get_disjoint_mut([a, b])
+        .expect("Invalid indices to unchecked function");
+    (r1, r2)
+}
+pub fn get_block_unchecked_mut(blocks: &mut [UiBlock], id: Id) -> &mut UiBlock {
+    blocks
+        .iter_mut()
+        .find(|b| b.id == id)
+        .unwrap_or_else(|| panic!("Block unexpectedly disappeared: {id}"))
+}
+
+pub fn get_points_for_link(b: &UiBlock, bp: &UiBlock) -> [Vector2; 2] {
+    let Vector2 { x: b_w, y: b_h } = b.dims();
+    let Vector2 { x: bp_w, y: bp_h } = bp.dims();
+    let b_c = b.center();
+    let bp_c = bp.center();
+    let m = LINK_MARGIN_BETWEEN_RADICAL;
+
+    //let angle = ;
+
+    // What follows is incredibly scuffed
+    // We divide the line into three cases (per rectangle): closest to short, closest
+    // to long and closest to corner.
+
+    // Assuming we're 'close' to top right corner
+    // A is the 'bottom' point of the rounding circle
+    // B is the 'left' point of the rounding circle
+    // i.e. they're both the same distance away from the corner (m)
+    //
+    // +--------B--+
+    // |           |
+    // |           A
+    // |           |
+    // |           |
+    // +-----------+
+    let a = Vector2 {
+        x: b_c.x + b_w / 2.0,
+        y: b_c.y + b_h / 2.0 - m,
+    };
+    let b = Vector2 {
+        x: b_c.x + b_w / 2.0 - m,
+        y: b_c.y + b_h / 2.0,
+    };
+    let alpha = (a.y / a.x).atan();
+    let beta = (b.y / b.x).atan();
+
+    // TODO: replace this with actual logic
+    [b_c, bp_c]
+}
+
+pub fn undo_last(st: &mut UiState) {
+    let Some(action) = st.undo_list.pop() else {
+        return;
+    };
+    eprintln!("dbg: Undoing last action: {action:x?}");
+
+    undo_action(st, action.clone());
+    st.redo_list.push(action.opposite());
+}
+pub fn redo_last(st: &mut UiState) {
+    let Some(action) = st.redo_list.pop() else {
+        return;
+    };
+    eprintln!("dbg: REdoing last action: {action:x?}");
+
+    undo_action(st, action.clone());
+    st.undo_list.push(action.opposite());
+}
+
+fn undo_action(st: &mut UiState, action: UiAction) {
+    if !matches!(action, UiAction::MoveRadicals(_)) {
+        st.labels.clear();
+        st.formula_panel = None;
+    }
+    match action {
+        UiAction::AddRadical(what) => {
+            if let Some(index) = st.uiblocks.iter().position(|b| b.id == what.id) {
+                st.uiblocks.remove(index);
+                remove_hanging_links(&mut st.uiblocks, what.id);
+            }
+        }
+        UiAction::MoveRadicals(data) => {
+            for (id, from, to) in data {
+                if let Some(block) = st.uiblocks.iter_mut().find(|b| b.id == id) {
+                    block.pos = from;
+                }
+            }
+        }
+        UiAction::DeleteRadical(what) => st.uiblocks.push(what),
+        UiAction::AddLink(a_link, b_link) => {
+            let (a, b) = get_two_blocks_unchecked_mut(&mut st.uiblocks, a_link.0, b_link.0);
+            if let Some(i) = a.links.iter().position(|&l| l == (b_link.0, a_link.1)) {
+                a.links.remove(i);
+            }
+            if let Some(i) = b.links.iter().position(|&l| l == (a_link.0, b_link.1)) {
+                b.links.remove(i);
+            }
+        }
+        UiAction::DeleteLink(a_link, b_link) => add_link(&mut st.uiblocks, a_link, b_link),
+    }
+}
+
+pub fn delete_under_cursor(st: &mut UiState, curr_mouse_pos: Vector2) {
+    if let Some(index) = st
+        .uiblocks
+        .iter()
+        .position(|b| is_point_in_block(curr_mouse_pos, b))
+    {
+        let what = st.uiblocks.remove(index);
+        remove_hanging_links(&mut st.uiblocks, what.id);
+        st.push_to_undo(UiAction::DeleteRadical(what));
+    } else {
+        // remove link if it exists
+        for ((a_id, b_id), m) in UiBlock::count_links(&st.uiblocks) {
+            let a = get_block_unchecked(&st.uiblocks, a_id);
+            let b = get_block_unchecked(&st.uiblocks, b_id);
+            if cursor_on_link(curr_mouse_pos, a, b, m)
+                && let Some((a_port, b_port)) = remove_link(&mut st.uiblocks, a_id, b_id)
+            {
+                st.push_to_undo(UiAction::DeleteLink((a_id, a_port), (b_id, b_port)));
+            }
+        }
+    }
+}
+
+/// Links `a` and `b`, each given as (id, port)
+pub fn add_link(blocks: &mut [UiBlock], (a_id, a_port): (Id, usize), (b_id, b_port): (Id, usize)) {
+    let (a, b) = get_two_blocks_unchecked_mut(blocks, a_id, b_id);
+    a.links.push((b_id, a_port));
+    b.links.push((a_id, b_port));
+}
+
+/// Removes one of the links between `a` and `b` and returns the ports it used, as (a's, b's).
+/// Does nothing if the links don't exist
+pub fn remove_link(blocks: &mut [UiBlock], a_id: Id, b_id: Id) -> Option<(usize, usize)> {
+    let (a, b) = get_two_blocks_unchecked_mut(blocks, a_id, b_id);
+    if let (Some(i_a), Some(i_b)) = (
+        a.links.iter().position(|&(l, _)| l == b_id),
+        b.links.iter().position(|&(l, _)| l == a_id),
+    ) {
+        let (_, a_port) = a.links.remove(i_a);
+        let (_, b_port) = b.links.remove(i_b);
+        Some((a_port, b_port))
+    } else {
+        eprintln!("Tried to remove a link between {a_id:x} and {b_id:x}, but there was none");
+        None
+    }
+}
+
+pub fn cursor_on_link(mouse: Vector2, a: &UiBlock, b: &UiBlock, multiplicitat: usize) -> bool {
+    let [c1, c2] = get_points_for_link(a, b);
+    // Assuming the line flows from c1->c2
+
+    let slope = (c2.y - c1.y) / (c2.x - c1.x);
+    let max_dist = (LINK_LINE_THICKNESS * 2.0 - 1.0) * 1.5; // give 50% margin
+    let dist = {
+        // line<->point distance
+        let (a, b, c) = (slope, -1.0, c1.y - slope * c1.x);
+        ((a * mouse.x + b * mouse.y + c) / (a * a + b * b).sqrt()).abs()
+    };
+    let is_in_between = c1.x.min(c2.x) <= mouse.x
+        && mouse.x <= c1.x.max(c2.x)
+        && c1.y.min(c2.y) <= mouse.y
+        && mouse.y <= c1.y.max(c2.y);
+
+    dist <= max_dist && is_in_between
+}
+
+impl Molecule {
+    /// The molecule made by `blocks`, with its groups in the same order
+    pub fn from_blocks(blocks: &[UiBlock]) -> Self {
+        // Each link shows up in the `links` of both blocks; they are paired in order
+        let mut links = vec![];
+        for (i, a) in blocks.iter().enumerate() {
+            for (j, b) in blocks.iter().enumerate().skip(i + 1) {
+                let de_a = a.links.iter().filter(|(id, _)| *id == b.id);
+                let de_b = b.links.iter().filter(|(id, _)| *id == a.id);
+                for ((_, p), (_, q)) in de_a.zip(de_b) {
+                    links.push(((i, *p), (j, *q)));
+                }
+            }
+        }
+        let groups: Vec<UiRadical> = blocks.iter().map(|b| b.radical).collect();
+        Self::from_links(&groups, &links)
+    }
+}
+
+/// Els blocs de la molècula (el component connex) de la qual forma part `source`
+fn blocs_de(input: &[UiBlock], source: &UiBlock) -> Vec<UiBlock> {
+    let index = input
+        .iter()
+        .position(|b| b.id == source.id)
+        .expect("block existed and then didn't in the same frame");
+    find_connex(input, index)
+}
+
+fn molecula_de(input: &[UiBlock], source: &UiBlock) -> Molecule {
+    Molecule::from_blocks(&blocs_de(input, source))
+}
+
+/// Anomena la molècula (el component connex) de la qual forma part `source`
+pub fn anomena(
+    input: &[UiBlock],
+    source: &UiBlock,
+    opts: &NamingOptions,
+) -> Result<Naming, NamingError> {
+    anomena_molecula(&molecula_de(input, source), opts)
+}
+
+/// Anomena cada molècula (component connex) de `input`, en l'ordre en què apareix el seu primer
+/// bloc. Retorna els ids dels blocs de cada una juntament amb el seu nom
+pub fn anomena_totes(
+    input: &[UiBlock],
+    opts: &NamingOptions,
+) -> Vec<(Vec<Id>, Result<Naming, NamingError>)> {
+    molecules(input)
+        .into_iter()
+        .map(|molecula| {
+            let ids = molecula.iter().map(|b| b.id).collect();
+            (
+                ids,
+                anomena_molecula(&Molecule::from_blocks(&molecula), opts),
+            )
+        })
+        .collect()
+}
+
+/// La fórmula de la molècula (el component connex) de la qual forma part `source`
+pub fn formula(input: &[UiBlock], source: &UiBlock) -> Result<Formula, NamingError> {
+    formula_molecula(&molecula_de(input, source))
+}
+
+/// La fórmula de cada molècula d'`input`, en el mateix ordre que `anomena_totes`
+pub fn formules(input: &[UiBlock]) -> Vec<(Vec<Id>, Result<Formula, NamingError>)> {
+    molecules(input)
+        .into_iter()
+        .map(|molecula| {
+            let ids = molecula.iter().map(|b| b.id).collect();
+            (ids, formula_molecula(&Molecule::from_blocks(&molecula)))
+        })
+        .collect()
+}
+
+/// Si les molècules on hi ha `a` i `b` són el mateix compost
+pub fn equivalents(input: &[UiBlock], a: &UiBlock, b: &UiBlock) -> bool {
+    son_equivalents(&molecula_de(input, a), &molecula_de(input, b))
+}
+
+/// Els ids dels blocs de la molècula on hi ha `source`
+pub fn connected_ids(input: &[UiBlock], source: &UiBlock) -> Vec<Id> {
+    blocs_de(input, source).iter().map(|b| b.id).collect()
+}
+
+/// El SMILES canònic de la molècula on hi ha `source`
+pub fn smiles(input: &[UiBlock], source: &UiBlock) -> String {
+    smiles_molecula(&molecula_de(input, source))
+}
+
+/// La molècula on hi ha `source` en molfile, amb el nom a la primera línia i les posicions
+/// del dibuix
+pub fn molfile(
+    input: &[UiBlock],
+    source: &UiBlock,
+    opts: &NamingOptions,
+) -> Result<String, MolfileError> {
+    let (molecula, posicions, titol) = per_fitxer(&blocs_de(input, source), opts);
+    molfile_molecula(&molecula, &posicions, &titol)
+}
+
+/// Totes les molècules d'`input` en SDF, en el mateix ordre que `anomena_totes`
+pub fn sdf(input: &[UiBlock], opts: &NamingOptions) -> Result<String, MolfileError> {
+    let molecules: Vec<_> = molecules(input)
+        .iter()
+        .map(|m| per_fitxer(m, opts))
+        .collect();
+    sdf_molecules(
+        molecules
+            .iter()
+            .map(|(m, posicions, titol)| (m, &posicions[..], &titol[..])),
+    )
+}
+
+/// La molècula, on és cada bloc en llargades d'enllaç i el nom, si en té
+fn per_fitxer(blocs: &[UiBlock], opts: &NamingOptions) -> (Molecule, Vec<(f32, f32)>, String) {
+    let molecula = Molecule::from_blocks(blocs);
+    let posicions = blocs
+        .iter()
+        .map(|b| (b.pos.x / STRUCTURE_SPACING.x, b.pos.y / STRUCTURE_SPACING.y))
+        .collect();
+    let titol = anomena_molecula(&molecula, opts)
+        .map(|nom| nom.to_string())
+        .unwrap_or_default();
+    (molecula, posicions, titol)
+}
+
+/// Without repetitions, a double bond is still only one neighbour
+fn get_adjacent(g: &[UiBlock], index: usize) -> Vec<usize> {
+    let mut adj: Vec<usize> = g[index]
+        .links
+        .iter()
+        .map(|(id, _)| g.iter().position(|b| b.id == *id).unwrap())
+        .collect();
+    adj.sort();
+    adj.dedup();
+    adj
+}
+
+/// Les molècules (components connexos) d'`input`, en l'ordre en què apareix el seu primer bloc
+fn molecules(input: &[UiBlock]) -> Vec<Vec<UiBlock>> {
+    let mut vist = vec![false; input.len()];
+    let mut molecules = vec![];
+    for i in 0..input.len() {
+        if vist[i] {
+            continue;
+        }
+        let molecula = find_connex(input, i);
+        for (j, b) in input.iter().enumerate() {
+            vist[j] |= molecula.iter().any(|m| m.id == b.id);
+        }
+        molecules.push(molecula);
+    }
+    molecules
+}
+
+/// Tots els blocs connectats amb el `index`-èssim
+fn find_connex(g: &[UiBlock], index: usize) -> Vec<UiBlock> {
+    let mut seen = vec![false; g.len()];
+    let mut pila = vec![index];
+    while let Some(i) = pila.pop() {
+        if !seen[i] {
+            seen[i] = true;
+            pila.extend(get_adjacent(g, i));
+        }
+    }
+    g.iter()
+        .cloned()
+        .enumerate()
+        .filter_map(|(i, n)| seen[i].then_some(n))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Un llenç buit amb l'etiqueta d'una molècula
+    fn amb_etiqueta() -> UiState {
+        UiState {
+            uiblocks: vec![],
+            held: None,
+            is_help_up: false,
+            undo_list: vec![],
+            redo_list: vec![],
+            window_dims: (800, 600),
+            naming_text: None,
+            naming_options: NamingOptions::default(),
+            name_input: None,
+            labels: vec![(vec![1, 2], "etè".to_string())],
+            formula_panel: None,
+            atom_palette: false,
+            selected: None,
+        }
+    }
+
+    /// Una font sense res. Com que no té textura, raylib no la descarrega en deixar-la anar
+    fn font() -> Rc<Font> {
+        Rc::new(unsafe { Font::from_raw(std::mem::zeroed()) })
+    }
+
+    #[test]
+    fn accions_sobre_una_molecula() {
+        let etanol = parse_smiles("CCO").unwrap();
+        let mut blocs = structure_blocks(&etanol, font(), Vector2::zero());
+        let ids: Vec<Id> = blocs.iter().map(|b| b.id).collect();
+        let dimetileter = parse_smiles("COC").unwrap();
+        blocs.extend(structure_blocks(&dimetileter, font(), Vector2::new(500.0, 0.0)));
+        let (a, b) = (&blocs[0], &blocs[blocs.len() - 1]);
+
+        let mut connectats = connected_ids(&blocs, a);
+        connectats.sort();
+        let mut ids_ = ids.clone();
+        ids_.sort();
+        assert_eq!(connectats, ids_);
+        let opts = NamingOptions::default();
+        assert_eq!(anomena(&blocs, a, &opts).unwrap().name, "etanol");
+        assert_eq!(formula(&blocs, a), formula(&blocs, b));
+        assert_eq!(smiles(&blocs, a), "CCO");
+        assert_eq!(smiles(&blocs, b), "COC");
+        assert!(!equivalents(&blocs, a, b));
+        assert!(equivalents(&blocs, a, &blocs[ids.len() - 1]));
+        assert!(molfile(&blocs, a, &opts).unwrap().starts_with("etanol\n"));
+    }
+
+    #[test]
+    fn etiquetes() {
+        // Moure blocs no canvia cap nom, ni quan es desfà o es refà
+        let mut st = amb_etiqueta();
+        st.push_to_undo(UiAction::MoveRadicals(vec![]));
+        undo_last(&mut st);
+        redo_last(&mut st);
+        assert_eq!(st.labels.len(), 1);
+        // Qualsevol altre canvi sí
+        st.push_to_undo(UiAction::DeleteLink((1, 0), (2, 0)));
+        assert!(st.labels.is_empty());
+    }
+}